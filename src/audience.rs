@@ -0,0 +1,26 @@
+use serde::Deserialize;
+
+// JWT style `aud` claim (RFC 7519 section 4.1.3) which is either a single
+// string or an array of strings.
+#[derive(Debug, Eq, PartialEq, Clone, Deserialize)]
+#[serde(untagged)]
+pub(crate) enum Audience {
+    Single(String),
+    Multiple(Vec<String>),
+}
+
+impl Audience {
+    pub(crate) fn to_vec(&self) -> Vec<String> {
+        match self {
+            Audience::Single(ref s) => vec![s.clone()],
+            Audience::Multiple(ref arr) => arr.clone(),
+        }
+    }
+
+    pub(crate) fn contains(&self, audience: &str) -> bool {
+        match self {
+            Audience::Single(ref s) => s == audience,
+            Audience::Multiple(ref arr) => arr.iter().any(|a| a == audience),
+        }
+    }
+}
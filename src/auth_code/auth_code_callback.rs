@@ -0,0 +1,223 @@
+use crate::utils::{self, FormDecodeError};
+use crate::AuthCodeError;
+use serde_json::{Map, Value};
+use std::error::Error;
+use std::fmt::Display;
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct AuthCodeResponse {
+    code: String,
+    state: Option<String>,
+}
+
+impl AuthCodeResponse {
+    pub fn code(&self) -> String {
+        self.code.clone()
+    }
+
+    pub fn state(&self) -> Option<String> {
+        self.state.clone()
+    }
+}
+
+#[derive(Debug)]
+pub enum AuthCodeCallbackError {
+    // the authorization server denied the request (RFC 6749 section 4.1.2.1).
+    AuthCode(AuthCodeError),
+    // the returned state does not match the one sent in the request, which
+    // means the response may be forged (RFC 6749 section 10.12).
+    StateMismatch,
+    MissingCode,
+    DuplicateParameter(String),
+    InvalidEncoding(FormDecodeError),
+    InvalidErrorResponse(serde_json::Error),
+}
+
+impl Display for AuthCodeCallbackError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        use AuthCodeCallbackError::*;
+        match self {
+            AuthCode(e) => write!(f, "AuthCodeCallbackError::AuthCode({e})"),
+            StateMismatch => write!(f, "AuthCodeCallbackError::StateMismatch"),
+            MissingCode => write!(f, "AuthCodeCallbackError::MissingCode"),
+            DuplicateParameter(p) => write!(f, "AuthCodeCallbackError::DuplicateParameter({p})"),
+            InvalidEncoding(e) => write!(f, "AuthCodeCallbackError::InvalidEncoding({e})"),
+            InvalidErrorResponse(e) => {
+                write!(f, "AuthCodeCallbackError::InvalidErrorResponse({e})")
+            }
+        }
+    }
+}
+
+impl Error for AuthCodeCallbackError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        use AuthCodeCallbackError::*;
+        match self {
+            AuthCode(e) => Some(e),
+            InvalidEncoding(e) => Some(e),
+            InvalidErrorResponse(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+// The response parameters are in the query component for the code flow, but
+// response_mode=fragment and the implicit flow put them in the fragment.
+fn get_response_component(url: &str) -> &str {
+    let (rest, fragment) = match url.split_once('#') {
+        Some((rest, fragment)) => (rest, fragment),
+        None => (url, ""),
+    };
+    let query = rest.split_once('?').map(|(_, q)| q).unwrap_or_default();
+    let has_response = |component: &str| {
+        component.split('&').any(|p| {
+            let key = p.split('=').next().unwrap_or_default();
+            key == "code" || key == "error"
+        })
+    };
+    if !has_response(query) && has_response(fragment) {
+        fragment
+    } else {
+        query
+    }
+}
+
+pub(crate) fn parse_callback_url(
+    url: &str,
+    expected_state: Option<&str>,
+) -> Result<AuthCodeResponse, AuthCodeCallbackError> {
+    let params = utils::decode_form_params(get_response_component(url).as_bytes())
+        .map_err(AuthCodeCallbackError::InvalidEncoding)?;
+
+    let mut fields = Map::new();
+    for (key, value) in params {
+        if fields.contains_key(&key) {
+            return Err(AuthCodeCallbackError::DuplicateParameter(key));
+        }
+        fields.insert(key, Value::String(value));
+    }
+
+    let state = fields.get("state").and_then(Value::as_str);
+    if let Some(expected) = expected_state {
+        match state {
+            Some(state) if utils::constant_time_eq(state.as_bytes(), expected.as_bytes()) => {}
+            _ => return Err(AuthCodeCallbackError::StateMismatch),
+        }
+    }
+    let state = state.map(String::from);
+
+    if fields.contains_key("error") {
+        let error: AuthCodeError = serde_json::from_value(Value::Object(fields))
+            .map_err(AuthCodeCallbackError::InvalidErrorResponse)?;
+        return Err(AuthCodeCallbackError::AuthCode(error));
+    }
+
+    match fields.remove("code") {
+        Some(Value::String(code)) => Ok(AuthCodeResponse { code, state }),
+        _ => Err(AuthCodeCallbackError::MissingCode),
+    }
+}
+
+#[cfg(test)]
+mod auth_code_callback_tests {
+    use super::*;
+    use crate::AuthCodeErrorKind;
+
+    #[test]
+    fn test_parse_callback_url_query() {
+        let res = parse_callback_url(
+            "https://client.example.com/cb?code=SplxlOBeZQQYbYS6WxSbIA&state=xyz%20abc",
+            Some("xyz abc"),
+        )
+        .unwrap();
+        assert_eq!(res.code(), "SplxlOBeZQQYbYS6WxSbIA");
+        assert_eq!(res.state(), Some("xyz abc".into()));
+    }
+
+    #[test]
+    fn test_parse_callback_url_fragment() {
+        let res = parse_callback_url(
+            "https://client.example.com/cb?foo=bar#code=SplxlOBeZQQYbYS6WxSbIA&state=xyz",
+            Some("xyz"),
+        )
+        .unwrap();
+        assert_eq!(res.code(), "SplxlOBeZQQYbYS6WxSbIA");
+    }
+
+    #[test]
+    fn test_parse_callback_url_without_expected_state() {
+        let res =
+            parse_callback_url("https://client.example.com/cb?code=abc&state=xyz", None).unwrap();
+        assert_eq!(res.code(), "abc");
+        assert_eq!(res.state(), Some("xyz".into()));
+    }
+
+    #[test]
+    fn test_parse_callback_url_state_mismatch() {
+        let res = parse_callback_url(
+            "https://client.example.com/cb?code=abc&state=attacker",
+            Some("xyz"),
+        );
+        assert!(matches!(res, Err(AuthCodeCallbackError::StateMismatch)));
+
+        let res = parse_callback_url("https://client.example.com/cb?code=abc", Some("xyz"));
+        assert!(matches!(res, Err(AuthCodeCallbackError::StateMismatch)));
+    }
+
+    #[test]
+    fn test_parse_callback_url_error() {
+        let res = parse_callback_url(
+            "https://client.example.com/cb?error=access_denied\
+             &error_description=user+declined&state=xyz",
+            Some("xyz"),
+        );
+        match res {
+            Err(AuthCodeCallbackError::AuthCode(e)) => {
+                assert!(matches!(e.error(), AuthCodeErrorKind::AccessDenied));
+                assert_eq!(e.error_description(), Some("user declined".into()));
+                assert_eq!(e.state(), Some("xyz".into()));
+            }
+            res => panic!("unexpected result: {res:?}"),
+        }
+    }
+
+    #[test]
+    fn test_parse_callback_url_unknown_error() {
+        let res = parse_callback_url(
+            "https://client.example.com/cb?error=login_required&state=xyz",
+            Some("xyz"),
+        );
+        match res {
+            Err(AuthCodeCallbackError::AuthCode(e)) => {
+                assert!(
+                    matches!(e.error(), AuthCodeErrorKind::Other(code) if code == "login_required")
+                );
+            }
+            res => panic!("unexpected result: {res:?}"),
+        }
+    }
+
+    #[test]
+    fn test_parse_callback_url_error_state_mismatch() {
+        let res = parse_callback_url(
+            "https://client.example.com/cb?error=access_denied&state=attacker",
+            Some("xyz"),
+        );
+        assert!(matches!(res, Err(AuthCodeCallbackError::StateMismatch)));
+    }
+
+    #[test]
+    fn test_parse_callback_url_missing_code() {
+        let res = parse_callback_url("https://client.example.com/cb?state=xyz", Some("xyz"));
+        assert!(matches!(res, Err(AuthCodeCallbackError::MissingCode)));
+    }
+
+    #[test]
+    fn test_parse_callback_url_duplicate_parameter() {
+        let res = parse_callback_url("https://client.example.com/cb?code=a&code=b", None);
+        assert!(matches!(
+            res,
+            Err(AuthCodeCallbackError::DuplicateParameter(p)) if p == "code"
+        ));
+    }
+}
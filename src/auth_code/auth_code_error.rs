@@ -12,6 +12,10 @@ pub enum AuthCodeErrorKind {
     InvalidScope,
     ServerError,
     TemporarilyUnavailable,
+    // any error code this crate does not know about, e.g. the OpenID Connect
+    // login_required or one specific to a server.
+    #[serde(untagged)]
+    Other(String),
 }
 
 impl Display for AuthCodeErrorKind {
@@ -28,6 +32,7 @@ impl Display for AuthCodeErrorKind {
                 InvalidScope => "InvalidScope",
                 ServerError => "ServerError",
                 TemporarilyUnavailable => "TemporarilyUnavailable",
+                Other(code) => return write!(f, "AuthErrorKind::Other({code})"),
             }
         )
     }
@@ -57,3 +62,21 @@ impl Display for AuthCodeError {
 }
 
 impl Error for AuthCodeError {}
+
+impl AuthCodeError {
+    pub fn error(&self) -> &AuthCodeErrorKind {
+        &self.error
+    }
+
+    pub fn error_description(&self) -> Option<String> {
+        self.error_description.clone()
+    }
+
+    pub fn error_uri(&self) -> Option<String> {
+        self.error_uri.clone()
+    }
+
+    pub fn state(&self) -> Option<String> {
+        self.state.clone()
+    }
+}
@@ -1,5 +1,10 @@
+use super::auth_code_callback;
+use crate::oidc;
 use crate::utils;
-use crate::OAuthParams;
+use crate::{
+    AuthCodeCallbackError, AuthCodeResponse, ChallengMethod, ClaimsRequest, CodeVerifier,
+    DisplayMode, Nonce, OAuthParams, Prompt, PromptError, State,
+};
 
 #[derive(Default, Debug, PartialEq, Eq)]
 pub struct AuthCodeRequest {
@@ -8,7 +13,18 @@ pub struct AuthCodeRequest {
     redirect_url: Option<String>,
     scope: Option<Vec<String>>,
     state: Option<String>,
+    nonce: Option<String>,
+    prompt: Option<String>,
+    max_age: Option<String>,
+    ui_locales: Option<String>,
+    login_hint: Option<String>,
+    id_token_hint: Option<String>,
+    acr_values: Option<String>,
+    display: Option<String>,
+    claims: Option<String>,
     extras: Option<Vec<(String, String)>>,
+    code_challenge: Option<String>,
+    code_challenge_method: Option<String>,
 }
 
 impl OAuthParams for AuthCodeRequest {
@@ -39,6 +55,42 @@ impl OAuthParams for AuthCodeRequest {
         self.state.clone()
     }
 
+    fn get_nonce(&self) -> Option<String> {
+        self.nonce.clone()
+    }
+
+    fn get_prompt(&self) -> Option<String> {
+        self.prompt.clone()
+    }
+
+    fn get_max_age(&self) -> Option<String> {
+        self.max_age.clone()
+    }
+
+    fn get_ui_locales(&self) -> Option<String> {
+        self.ui_locales.clone()
+    }
+
+    fn get_login_hint(&self) -> Option<String> {
+        self.login_hint.clone()
+    }
+
+    fn get_id_token_hint(&self) -> Option<String> {
+        self.id_token_hint.clone()
+    }
+
+    fn get_acr_values(&self) -> Option<String> {
+        self.acr_values.clone()
+    }
+
+    fn get_display(&self) -> Option<String> {
+        self.display.clone()
+    }
+
+    fn get_claims(&self) -> Option<String> {
+        self.claims.clone()
+    }
+
     fn get_extra_params_mut(&mut self) -> Option<&mut Vec<(String, String)>> {
         if self.extras.is_none() {
             self.extras = Some(vec![])
@@ -49,6 +101,14 @@ impl OAuthParams for AuthCodeRequest {
     fn get_extra_params_ref(&self) -> Option<&Vec<(String, String)>> {
         self.extras.as_ref()
     }
+
+    fn get_code_challenge(&self) -> Option<String> {
+        self.code_challenge.clone()
+    }
+
+    fn get_code_challenge_method(&self) -> Option<String> {
+        self.code_challenge_method.clone()
+    }
 }
 
 impl AuthCodeRequest {
@@ -59,7 +119,18 @@ impl AuthCodeRequest {
             redirect_url: None,
             scope: None,
             state: None,
+            nonce: None,
+            prompt: None,
+            max_age: None,
+            ui_locales: None,
+            login_hint: None,
+            id_token_hint: None,
+            acr_values: None,
+            display: None,
+            claims: None,
             extras: None,
+            code_challenge: None,
+            code_challenge_method: None,
         }
     }
 
@@ -71,6 +142,97 @@ impl AuthCodeRequest {
         self.state = Some(state);
     }
 
+    // returns the state set on the request, generating a random one first
+    // if the caller has not set any.
+    pub fn get_or_generate_state(&mut self) -> String {
+        self.state
+            .get_or_insert_with(|| State::new().secret())
+            .clone()
+    }
+
+    pub fn set_nonce(&mut self, nonce: &Nonce) {
+        self.add_openid_scope();
+        self.nonce = Some(nonce.secret());
+    }
+
+    pub fn get_nonce(&self) -> Option<String> {
+        self.nonce.clone()
+    }
+
+    // returns the nonce set on the request, generating a random one first
+    // if the caller has not set any.
+    pub fn get_or_generate_nonce(&mut self) -> String {
+        self.add_openid_scope();
+        self.nonce
+            .get_or_insert_with(|| Nonce::new().secret())
+            .clone()
+    }
+
+    pub fn set_prompt(&mut self, prompt: &[Prompt]) -> Result<(), PromptError> {
+        let prompt = oidc::prompt_to_string(prompt)?;
+        self.add_openid_scope();
+        self.prompt = Some(prompt);
+        Ok(())
+    }
+
+    pub fn set_max_age(&mut self, max_age: u64) {
+        self.add_openid_scope();
+        self.max_age = Some(max_age.to_string());
+    }
+
+    // BCP47 language tags in order of preference.
+    pub fn set_ui_locales(&mut self, ui_locales: &[&str]) {
+        self.add_openid_scope();
+        self.ui_locales = Some(ui_locales.join(" "));
+    }
+
+    pub fn set_login_hint(&mut self, login_hint: String) {
+        self.add_openid_scope();
+        self.login_hint = Some(login_hint);
+    }
+
+    pub fn set_id_token_hint(&mut self, id_token_hint: String) {
+        self.add_openid_scope();
+        self.id_token_hint = Some(id_token_hint);
+    }
+
+    // Authentication Context Class References in order of preference.
+    pub fn set_acr_values(&mut self, acr_values: &[&str]) {
+        self.add_openid_scope();
+        self.acr_values = Some(acr_values.join(" "));
+    }
+
+    pub fn set_display(&mut self, display: DisplayMode) {
+        self.add_openid_scope();
+        self.display = Some(display.as_str().to_owned());
+    }
+
+    pub fn set_claims(&mut self, claims: &ClaimsRequest) {
+        self.add_openid_scope();
+        self.claims = Some(claims.to_json());
+    }
+
+    // OpenID Connect parameters only have a meaning in an OpenID Connect
+    // request, which is one with the `openid` scope (Core 1.0 section 3.1.2.1).
+    fn add_openid_scope(&mut self) {
+        let has_openid = self
+            .scope
+            .iter()
+            .flatten()
+            .flat_map(|s| s.split_whitespace())
+            .any(|s| s == "openid");
+        if !has_openid {
+            self.scope
+                .get_or_insert_with(Vec::new)
+                .insert(0, "openid".into());
+        }
+    }
+
+    pub fn set_code_challenge(&mut self, verifier: &CodeVerifier, method: ChallengMethod) {
+        self.code_challenge = Some(verifier.get_code_challange(method));
+        self.code_challenge_method = Some(method.as_str().to_owned());
+    }
+
     pub fn add_scope(&mut self, scope: String) {
         utils::add_scope(self, scope);
     }
@@ -91,13 +253,28 @@ impl AuthCodeRequest {
     }
 
     pub fn get_headers(&self) -> Vec<(String, String)> {
-        utils::get_headers()
+        utils::get_headers(self)
+    }
+
+    // the URL only holds a state if one is set on the request. call
+    // get_or_generate_state first to have a random one generated and kept,
+    // so that parse_callback_url can check it.
+    pub fn get_authorization_url(&self, auth_endpoint: &str) -> String {
+        utils::get_url_with_params(auth_endpoint, self)
+    }
+
+    pub fn parse_callback_url(
+        &self,
+        callback_url: &str,
+    ) -> Result<AuthCodeResponse, AuthCodeCallbackError> {
+        auth_code_callback::parse_callback_url(callback_url, self.state.as_deref())
     }
 }
 
 #[cfg(test)]
 mod auth_code_request_tests {
     use super::AuthCodeRequest;
+    use crate::{ChallengMethod, CodeVerifier};
 
     #[test]
     fn test_default() {
@@ -110,7 +287,18 @@ mod auth_code_request_tests {
                 redirect_url: None,
                 scope: None,
                 state: None,
-                extras: None
+                nonce: None,
+                prompt: None,
+                max_age: None,
+                ui_locales: None,
+                login_hint: None,
+                id_token_hint: None,
+                acr_values: None,
+                display: None,
+                claims: None,
+                extras: None,
+                code_challenge: None,
+                code_challenge_method: None,
             }
         );
     }
@@ -126,7 +314,18 @@ mod auth_code_request_tests {
                 redirect_url: None,
                 scope: None,
                 state: None,
-                extras: None
+                nonce: None,
+                prompt: None,
+                max_age: None,
+                ui_locales: None,
+                login_hint: None,
+                id_token_hint: None,
+                acr_values: None,
+                display: None,
+                claims: None,
+                extras: None,
+                code_challenge: None,
+                code_challenge_method: None,
             }
         )
     }
@@ -167,4 +366,175 @@ mod auth_code_request_tests {
         assert!(params.contains(&("access_type".into(), "offline".into())));
         assert_eq!(params.len(), 7);
     }
+
+    #[test]
+    fn test_get_authorization_url() {
+        let mut request = AuthCodeRequest::new("test_id".into(), "code".into());
+        request.add_scopes(["openid".to_owned(), "email".to_owned()]);
+        request.set_state("a+b c".into());
+        request.set_redirect_url("https://client.example.com/cb?x=1".into());
+
+        let url = request.get_authorization_url("https://server.example.com/authorize");
+        assert_eq!(
+            url,
+            "https://server.example.com/authorize?response_type=code\
+             &redirect_uri=https%3A%2F%2Fclient.example.com%2Fcb%3Fx%3D1\
+             &client_id=test_id&scope=openid+email&state=a%2Bb+c"
+        );
+    }
+
+    #[test]
+    fn test_get_authorization_url_existing_query() {
+        let request = AuthCodeRequest::new("test_id".into(), "code".into());
+        let url = request.get_authorization_url(
+            "https://server.example.com/authorize?tenant=abc&client_id=x#frag",
+        );
+        assert_eq!(
+            url,
+            "https://server.example.com/authorize?tenant=abc&response_type=code&client_id=test_id"
+        );
+    }
+
+    #[test]
+    fn test_get_authorization_url_empty_query() {
+        let request = AuthCodeRequest::new("test_id".into(), "code".into());
+        let url = request.get_authorization_url("https://server.example.com/authorize?");
+        assert_eq!(
+            url,
+            "https://server.example.com/authorize?response_type=code&client_id=test_id"
+        );
+    }
+
+    #[test]
+    fn test_set_code_challenge_s256() {
+        let verifier = CodeVerifier::new();
+        let mut request = AuthCodeRequest::new("test_id".into(), "code".into());
+        request.set_code_challenge(&verifier, ChallengMethod::Sha256);
+
+        let params = request.get_request_params_as_vec();
+        assert!(params.contains(&(
+            "code_challenge".into(),
+            verifier.get_code_challange(ChallengMethod::Sha256)
+        )));
+        assert!(params.contains(&("code_challenge_method".into(), "S256".into())));
+    }
+
+    #[test]
+    fn test_set_code_challenge_plain() {
+        let verifier = CodeVerifier::new();
+        let mut request = AuthCodeRequest::new("test_id".into(), "code".into());
+        request.set_code_challenge(&verifier, ChallengMethod::Plain);
+
+        let params = request.get_request_params_as_vec();
+        assert!(params.contains(&("code_challenge".into(), verifier.get_code_verifier())));
+        assert!(params.contains(&("code_challenge_method".into(), "plain".into())));
+    }
+
+    #[test]
+    fn test_parse_callback_url_checks_state() {
+        let mut request = AuthCodeRequest::new("test_id".into(), "code".into());
+        request.set_state("expected_state".into());
+
+        let res = request
+            .parse_callback_url("https://client.example.com/cb?code=abc&state=expected_state");
+        assert_eq!(res.unwrap().code(), "abc");
+
+        let res = request.parse_callback_url("https://client.example.com/cb?code=abc&state=other");
+        assert!(matches!(
+            res,
+            Err(crate::AuthCodeCallbackError::StateMismatch)
+        ));
+    }
+
+    #[test]
+    fn test_get_or_generate_state() {
+        let mut request = AuthCodeRequest::new("test_id".into(), "code".into());
+        let state = request.get_or_generate_state();
+        assert_eq!(state.len(), 43);
+        assert_eq!(request.get_or_generate_state(), state);
+        assert_ne!(
+            AuthCodeRequest::new("test_id".into(), "code".into()).get_or_generate_state(),
+            state
+        );
+
+        request.set_state("fixed".into());
+        assert_eq!(request.get_or_generate_state(), "fixed");
+    }
+
+    #[test]
+    fn test_get_authorization_url_with_generated_state() {
+        let mut request = AuthCodeRequest::new("test_id".into(), "code".into());
+        assert!(!request
+            .get_authorization_url("https://example.com/auth")
+            .contains("state="));
+
+        let state = request.get_or_generate_state();
+        let url = request.get_authorization_url("https://example.com/auth");
+        assert!(url.ends_with(&format!("&state={state}")));
+
+        let res = request.parse_callback_url(&format!(
+            "https://client.example.com/cb?code=abc&state={state}"
+        ));
+        assert_eq!(res.unwrap().code(), "abc");
+    }
+
+    #[test]
+    fn test_nonce() {
+        let mut request = AuthCodeRequest::new("test_id".into(), "code".into());
+        assert_eq!(request.get_nonce(), None);
+        let nonce = request.get_or_generate_nonce();
+        assert_eq!(request.get_nonce(), Some(nonce));
+
+        request.set_nonce(&crate::Nonce::from_string("n-0S6_WzA2Mj".into()));
+        assert!(request
+            .get_request_params_as_vec()
+            .contains(&("nonce".to_owned(), "n-0S6_WzA2Mj".to_owned())));
+    }
+
+    #[test]
+    fn test_oidc_params() {
+        use crate::{ClaimsRequest, DisplayMode, IndividualClaimRequest, Prompt, PromptError};
+
+        let mut request = AuthCodeRequest::new("s6BhdRkqt3".into(), "code".into());
+        request.add_scope("profile".into());
+        request.set_nonce(&crate::Nonce::from_string("n-0S6_WzA2Mj".into()));
+        assert_eq!(
+            request.set_prompt(&[Prompt::None, Prompt::Consent]),
+            Err(PromptError::NoneWithOtherValues)
+        );
+        request
+            .set_prompt(&[Prompt::Login, Prompt::Consent])
+            .unwrap();
+        request.set_max_age(3600);
+        request.set_ui_locales(&["fr-CA", "fr", "en"]);
+        request.set_login_hint("janedoe@example.com".into());
+        request.set_acr_values(&["urn:mace:incommon:iap:silver"]);
+        request.set_display(DisplayMode::Popup);
+        let mut claims = ClaimsRequest::new();
+        claims.add_userinfo_claim("email".into(), IndividualClaimRequest::essential());
+        request.set_claims(&claims);
+
+        assert_eq!(
+            request.get_authorization_url("https://server.example.com/authorize"),
+            "https://server.example.com/authorize?response_type=code&client_id=s6BhdRkqt3\
+             &scope=openid+profile&nonce=n-0S6_WzA2Mj&prompt=login+consent&max_age=3600\
+             &ui_locales=fr-CA+fr+en&login_hint=janedoe%40example.com\
+             &acr_values=urn%3Amace%3Aincommon%3Aiap%3Asilver&display=popup\
+             &claims=%7B%22userinfo%22%3A%7B%22email%22%3A%7B%22essential%22%3Atrue%7D%7D%7D"
+        );
+    }
+
+    #[test]
+    fn test_openid_scope_added_once() {
+        let mut request = AuthCodeRequest::new("s6BhdRkqt3".into(), "code".into());
+        request.set_id_token_hint("eyJhbGciOiJSUzI1NiJ9.e30.c2ln".into());
+        assert_eq!(request.scope, Some(vec!["openid".to_owned()]));
+        request.get_or_generate_nonce();
+        assert_eq!(request.scope, Some(vec!["openid".to_owned()]));
+
+        let mut request = AuthCodeRequest::new("s6BhdRkqt3".into(), "code".into());
+        request.add_scope("email openid".into());
+        request.set_max_age(0);
+        assert_eq!(request.scope, Some(vec!["email openid".to_owned()]));
+    }
 }
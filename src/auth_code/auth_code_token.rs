@@ -1,65 +1,10 @@
+use crate::scope::Scope;
+use crate::utils;
 use crate::Token;
-use serde::{self, Deserialize, Deserializer};
+use serde::{self, Deserialize};
 use std::cmp::PartialEq;
-use std::fmt;
 use std::time::SystemTime;
 
-#[derive(Debug, Eq, PartialEq, Clone)]
-enum Scope {
-    Array(Vec<String>),
-    Str(String),
-}
-
-struct MyVisitor;
-
-impl<'de> serde::de::Visitor<'de> for MyVisitor {
-    type Value = Scope;
-
-    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
-        write!(f, "Failed to parse scopes")?;
-        Ok(())
-    }
-
-    fn visit_seq<A>(self, mut value: A) -> Result<Self::Value, A::Error>
-    where
-        A: serde::de::SeqAccess<'de>,
-    {
-        let mut vector: Vec<String> = Vec::with_capacity(value.size_hint().unwrap_or(0));
-        loop {
-            let element = value.next_element()?;
-            match element {
-                Some(data) => vector.push(data),
-                None => break,
-            }
-        }
-        Ok(Scope::Array(vector))
-    }
-
-    fn visit_str<E>(self, value: &str) -> Result<Self::Value, E>
-    where
-        E: serde::de::Error,
-    {
-        Ok(Scope::Str(String::from(value)))
-    }
-
-    fn visit_string<E>(self, v: String) -> Result<Self::Value, E>
-    where
-        E: serde::de::Error,
-    {
-        Ok(Scope::Str(v))
-    }
-}
-
-impl<'de> serde::de::Deserialize<'de> for Scope {
-    fn deserialize<D>(d: D) -> Result<Self, D::Error>
-    where
-        D: Deserializer<'de>,
-    {
-        let visitor = MyVisitor {};
-        d.deserialize_any(visitor)
-    }
-}
-
 #[derive(Debug, Eq, Deserialize)]
 pub struct AuthCodeToken {
     access_token: String,
@@ -67,6 +12,8 @@ pub struct AuthCodeToken {
     refresh_token: Option<String>,
     expires_in: Option<u32>,
     scope: Option<Scope>,
+    // OpenID Connect Core 1.0 section 3.1.3.3
+    id_token: Option<String>,
     #[serde(skip, default = "SystemTime::now")]
     generated_time: SystemTime,
 }
@@ -78,6 +25,7 @@ impl PartialEq for AuthCodeToken {
             && self.refresh_token == other.refresh_token
             && self.expires_in == other.expires_in
             && self.scope == other.scope
+            && self.id_token == other.id_token
     }
 }
 
@@ -107,28 +55,11 @@ impl Token for AuthCodeToken {
     }
 
     fn scopes(&self) -> Option<String> {
-        self.scope.clone().map(|v| match v {
-            Scope::Array(ref arr) => arr.join(" "),
-            Scope::Str(ref s) => s.clone(),
-        })
+        self.scope.as_ref().map(Scope::to_space_separated)
     }
 
     fn is_valid(&self) -> bool {
-        match self.expires_in {
-            Some(expires_in) => {
-                match self.generated_time.elapsed() {
-                    Ok(elapsed) => (elapsed.as_secs() as u32) < expires_in,
-                    // if err, then assume that the token is valid so that the
-                    // user can try accessing the protected resource using the
-                    // current token.
-                    Err(_) => true,
-                }
-            }
-            // if None, then the auth server did not provide the expiration
-            // info. So, there is no other choice but to use the access token
-            // and try to access the protected resource.
-            None => true,
-        }
+        utils::is_token_valid(self.generated_time, self.expires_in)
     }
 }
 
@@ -140,12 +71,18 @@ impl AuthCodeToken {
             refresh_token: None,
             expires_in: None,
             scope: None,
+            id_token: None,
             generated_time: SystemTime::now(),
         }
     }
+
+    pub fn id_token(&self) -> Option<String> {
+        self.id_token.clone()
+    }
 }
 
 #[cfg(test)]
+#[allow(clippy::bool_assert_comparison, clippy::single_component_path_imports)]
 mod tests {
     use super::*;
     use serde_json;
@@ -159,6 +96,7 @@ mod tests {
             refresh_token: Some("test_refresh_token".into()),
             expires_in: Some(3600),
             scope: None,
+            id_token: None,
             generated_time: now.checked_sub(Duration::from_secs(3599)).unwrap(),
         };
         assert_eq!(token.is_valid(), true);
@@ -173,6 +111,7 @@ mod tests {
             refresh_token: Some("test_refresh_token".into()),
             expires_in: Some(3600),
             scope: None,
+            id_token: None,
             generated_time: now.checked_sub(Duration::from_secs(3600)).unwrap(),
         };
         assert_eq!(token.is_valid(), false);
@@ -220,5 +159,20 @@ mod tests {
         }"#;
         let token: AuthCodeToken = serde_json::from_str(json_data).unwrap();
         assert_eq!(token.scopes(), None);
+        assert_eq!(token.id_token(), None);
+    }
+
+    #[test]
+    fn test_auth_token_deserializtion_id_token() {
+        let json_data = r#"{
+            "access_token": "test_token",
+            "token_type": "Bearer",
+            "id_token": "eyJhbGciOiJSUzI1NiJ9.e30.c2ln"
+        }"#;
+        let token: AuthCodeToken = serde_json::from_str(json_data).unwrap();
+        assert_eq!(
+            token.id_token(),
+            Some("eyJhbGciOiJSUzI1NiJ9.e30.c2ln".to_owned())
+        );
     }
 }
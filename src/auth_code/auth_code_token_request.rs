@@ -1,14 +1,17 @@
+use crate::http::{AsyncHttpClient, HttpClient, RequestError};
 use crate::utils;
-use crate::OAuthParams;
+use crate::{ClientAuth, CodeVerifier, OAuthParams, Token};
+use serde::de::DeserializeOwned;
 
 #[derive(Debug)]
 pub struct AuthCodeAccessTokenRequest {
     code: String,
     redirect_url: String,
-    client_id: String,
-    client_secret: Option<String>,
     scope: Option<Vec<String>>,
     extras: Option<Vec<(String, String)>>,
+    code_verifier: Option<String>,
+    // also holds the client_id, which is only sent from here.
+    client_auth: ClientAuth,
 }
 
 impl OAuthParams for AuthCodeAccessTokenRequest {
@@ -24,14 +27,6 @@ impl OAuthParams for AuthCodeAccessTokenRequest {
         self.redirect_url.clone().into()
     }
 
-    fn get_client_id(&self) -> Option<String> {
-        self.client_id.clone().into()
-    }
-
-    fn get_client_secret(&self) -> Option<String> {
-        self.client_secret.clone()
-    }
-
     fn get_scopes_mut(&mut self) -> Option<&mut Vec<String>> {
         if self.scope.is_none() {
             self.scope = Some(vec![])
@@ -53,6 +48,14 @@ impl OAuthParams for AuthCodeAccessTokenRequest {
     fn get_extra_params_ref(&self) -> Option<&Vec<(String, String)>> {
         self.extras.as_ref()
     }
+
+    fn get_client_auth(&self) -> Option<&ClientAuth> {
+        Some(&self.client_auth)
+    }
+
+    fn get_code_verifier(&self) -> Option<String> {
+        self.code_verifier.clone()
+    }
 }
 
 impl AuthCodeAccessTokenRequest {
@@ -60,15 +63,30 @@ impl AuthCodeAccessTokenRequest {
         AuthCodeAccessTokenRequest {
             code,
             redirect_url,
-            client_id,
-            client_secret: None,
             scope: None,
             extras: None,
+            code_verifier: None,
+            client_auth: ClientAuth::None { client_id },
         }
     }
 
+    // switches to client_secret_post for the client_id the request currently
+    // authenticates as.
     pub fn set_client_secret(&mut self, secret: String) {
-        self.client_secret = Some(secret);
+        self.client_auth = ClientAuth::ClientSecretPost {
+            client_id: self.client_auth.get_client_id().unwrap_or_default(),
+            client_secret: secret,
+        };
+    }
+
+    pub fn set_code_verifier(&mut self, verifier: &CodeVerifier) {
+        self.code_verifier = Some(verifier.get_code_verifier());
+    }
+
+    // replaces the client authentication, including the client_id given to
+    // `new`, so the request never carries two different client ids.
+    pub fn set_client_auth(&mut self, client_auth: ClientAuth) {
+        self.client_auth = client_auth;
     }
 
     pub fn add_scope(&mut self, scope: String) {
@@ -89,4 +107,116 @@ impl AuthCodeAccessTokenRequest {
     pub fn get_request_params_as_vec(&self) -> Vec<(String, String)> {
         utils::get_request_params_as_vec(self)
     }
+
+    pub fn get_headers(&self) -> Vec<(String, String)> {
+        utils::get_headers(self)
+    }
+
+    pub fn get_request_body(&self) -> Vec<u8> {
+        utils::get_request_body(self)
+    }
+
+    pub fn execute<C, T>(&self, client: &C, token_url: &str) -> Result<T, RequestError<C::Error>>
+    where
+        C: HttpClient,
+        T: Token + DeserializeOwned,
+    {
+        utils::execute_token_request(self, client, token_url)
+    }
+
+    pub async fn execute_async<C, T>(
+        &self,
+        client: &C,
+        token_url: &str,
+    ) -> Result<T, RequestError<C::Error>>
+    where
+        C: AsyncHttpClient,
+        T: Token + DeserializeOwned,
+    {
+        utils::execute_token_request_async(self, client, token_url).await
+    }
+}
+
+#[cfg(test)]
+mod auth_code_token_request_tests {
+    use super::*;
+    use crate::decode_form_params;
+
+    #[test]
+    fn test_get_request_body() {
+        let mut request = AuthCodeAccessTokenRequest::new(
+            "Splx/OR+zQbdSA".into(),
+            "https://client.example.com/cb".into(),
+            "s6BhdRkqt3".into(),
+        );
+        request.set_client_secret("7Fjfp0ZBr1KtDRbnfVdmIw&x".into());
+
+        let body = request.get_request_body();
+        assert_eq!(
+            String::from_utf8(body.clone()).unwrap(),
+            "grant_type=authorization_code\
+             &redirect_uri=https%3A%2F%2Fclient.example.com%2Fcb\
+             &client_id=s6BhdRkqt3&client_secret=7Fjfp0ZBr1KtDRbnfVdmIw%26x\
+             &code=Splx%2FOR%2BzQbdSA"
+        );
+        assert_eq!(
+            decode_form_params(&body).unwrap(),
+            request.get_request_params_as_vec()
+        );
+    }
+
+    #[test]
+    fn test_set_code_verifier() {
+        let verifier = CodeVerifier::new();
+        let mut request = AuthCodeAccessTokenRequest::new(
+            "Splx/OR+zQbdSA".into(),
+            "https://client.example.com/cb".into(),
+            "s6BhdRkqt3".into(),
+        );
+        request.set_code_verifier(&verifier);
+
+        let params = request.get_request_params_as_vec();
+        assert!(params.contains(&("code_verifier".into(), verifier.get_code_verifier())));
+    }
+
+    #[test]
+    fn test_public_client() {
+        let mut request = AuthCodeAccessTokenRequest::new(
+            "Splx/OR+zQbdSA".into(),
+            "https://client.example.com/cb".into(),
+            "s6BhdRkqt3".into(),
+        );
+        request.set_client_auth(ClientAuth::None {
+            client_id: "s6BhdRkqt3".into(),
+        });
+
+        let params = request.get_request_params_as_vec();
+        assert!(params.contains(&("client_id".into(), "s6BhdRkqt3".into())));
+        assert!(!params.iter().any(|(k, _)| k == "client_secret"));
+    }
+
+    #[test]
+    fn test_client_auth_replaces_client_id() {
+        let mut request = AuthCodeAccessTokenRequest::new(
+            "Splx/OR+zQbdSA".into(),
+            "https://client.example.com/cb".into(),
+            "s6BhdRkqt3".into(),
+        );
+        request.set_client_auth(ClientAuth::ClientSecretPost {
+            client_id: "other".into(),
+            client_secret: "secret".into(),
+        });
+
+        let client_ids = request
+            .get_request_params_as_vec()
+            .into_iter()
+            .filter(|(k, _)| k == "client_id")
+            .collect::<Vec<_>>();
+        assert_eq!(client_ids, vec![("client_id".into(), "other".into())]);
+
+        request.set_client_secret("rotated".into());
+        let params = request.get_request_params_as_vec();
+        assert!(params.contains(&("client_id".into(), "other".into())));
+        assert!(params.contains(&("client_secret".into(), "rotated".into())));
+    }
 }
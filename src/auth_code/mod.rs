@@ -1,8 +1,10 @@
+mod auth_code_callback;
 mod auth_code_error;
 mod auth_code_request;
 mod auth_code_token;
 mod auth_code_token_request;
 
+pub use auth_code_callback::{AuthCodeCallbackError, AuthCodeResponse};
 pub use auth_code_error::{AuthCodeError, AuthCodeErrorKind};
 pub use auth_code_request::AuthCodeRequest;
 pub use auth_code_token::AuthCodeToken;
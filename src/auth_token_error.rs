@@ -11,6 +11,17 @@ pub enum AuthTokenErrorKind {
     UnauthorizedClient,
     UnsupportedGrantType,
     InvalidScope,
+    // RFC 8628 section 3.5
+    AuthorizationPending,
+    SlowDown,
+    AccessDenied,
+    ExpiredToken,
+    // RFC 7009 section 2.2.1
+    UnsupportedTokenType,
+    // any error code this crate does not know about, e.g. one defined by an
+    // extension or specific to a server.
+    #[serde(untagged)]
+    Other(String),
 }
 
 impl Display for AuthTokenErrorKind {
@@ -26,6 +37,12 @@ impl Display for AuthTokenErrorKind {
                 UnauthorizedClient => "UnauthorizedClient",
                 UnsupportedGrantType => "UnsupportedGrantType",
                 InvalidScope => "InvalidScope",
+                AuthorizationPending => "AuthorizationPending",
+                SlowDown => "SlowDown",
+                AccessDenied => "AccessDenied",
+                ExpiredToken => "ExpiredToken",
+                UnsupportedTokenType => "UnsupportedTokenType",
+                Other(code) => return write!(f, "AuthCodeErrorKind::Other({code})"),
             }
         )
     }
@@ -40,6 +57,16 @@ pub struct AuthTokenError {
 
 impl Error for AuthTokenError {}
 
+impl AuthTokenError {
+    pub fn error(&self) -> &AuthTokenErrorKind {
+        &self.error
+    }
+
+    pub fn error_description(&self) -> Option<String> {
+        self.error_description.clone()
+    }
+}
+
 impl Display for AuthTokenError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
@@ -53,3 +80,29 @@ impl Display for AuthTokenError {
         )
     }
 }
+
+#[cfg(test)]
+mod auth_token_error_tests {
+    use super::*;
+
+    #[test]
+    fn test_known_error_code() {
+        let error: AuthTokenError = serde_json::from_str(r#"{"error":"invalid_grant"}"#).unwrap();
+        assert!(matches!(error.error(), AuthTokenErrorKind::InvalidGrant));
+    }
+
+    #[test]
+    fn test_unknown_error_code() {
+        let error: AuthTokenError =
+            serde_json::from_str(r#"{"error":"login_required","error_description":"no session"}"#)
+                .unwrap();
+        assert!(
+            matches!(error.error(), AuthTokenErrorKind::Other(code) if code == "login_required")
+        );
+        assert_eq!(error.error_description(), Some("no session".to_owned()));
+        assert_eq!(
+            serde_json::to_string(error.error()).unwrap(),
+            r#""login_required""#
+        );
+    }
+}
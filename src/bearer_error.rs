@@ -0,0 +1,208 @@
+use std::error::Error;
+use std::fmt::Display;
+use std::iter::Peekable;
+use std::str::Chars;
+
+// Error codes of a resource server (RFC 6750 section 3.1).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BearerErrorKind {
+    InvalidRequest,
+    InvalidToken,
+    InsufficientScope,
+    // any error code this crate does not know about.
+    Other(String),
+}
+
+impl From<&str> for BearerErrorKind {
+    fn from(s: &str) -> Self {
+        match s {
+            "invalid_request" => BearerErrorKind::InvalidRequest,
+            "invalid_token" => BearerErrorKind::InvalidToken,
+            "insufficient_scope" => BearerErrorKind::InsufficientScope,
+            other => BearerErrorKind::Other(other.to_owned()),
+        }
+    }
+}
+
+impl Display for BearerErrorKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        use BearerErrorKind::*;
+        match self {
+            InvalidRequest => write!(f, "BearerErrorKind::InvalidRequest"),
+            InvalidToken => write!(f, "BearerErrorKind::InvalidToken"),
+            InsufficientScope => write!(f, "BearerErrorKind::InsufficientScope"),
+            Other(code) => write!(f, "BearerErrorKind::Other({code})"),
+        }
+    }
+}
+
+// Error returned by a resource server in the `WWW-Authenticate` header of a
+// response to a request made with a bearer token (RFC 6750 section 3).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BearerError {
+    error: BearerErrorKind,
+    error_description: Option<String>,
+    error_uri: Option<String>,
+    scope: Option<String>,
+}
+
+impl BearerError {
+    // None unless the header holds a Bearer challenge with an `error`
+    // attribute, which servers leave out when no token was sent at all.
+    pub fn from_www_authenticate(header: &str) -> Option<Self> {
+        let (_, params) = parse_challenges(header)
+            .into_iter()
+            .find(|(scheme, _)| scheme.eq_ignore_ascii_case("Bearer"))?;
+        let get = |name: &str| {
+            params
+                .iter()
+                .find(|(k, _)| k.eq_ignore_ascii_case(name))
+                .map(|(_, v)| v.clone())
+        };
+        Some(Self {
+            error: BearerErrorKind::from(get("error")?.as_str()),
+            error_description: get("error_description"),
+            error_uri: get("error_uri"),
+            scope: get("scope"),
+        })
+    }
+
+    pub fn error(&self) -> &BearerErrorKind {
+        &self.error
+    }
+
+    pub fn error_description(&self) -> Option<String> {
+        self.error_description.clone()
+    }
+
+    pub fn error_uri(&self) -> Option<String> {
+        self.error_uri.clone()
+    }
+
+    // the scope needed to access the resource, for insufficient_scope.
+    pub fn scope(&self) -> Option<String> {
+        self.scope.clone()
+    }
+}
+
+impl Error for BearerError {}
+
+impl Display for BearerError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "BearerError(error = {}, error_description = {}, error_uri = {})",
+            self.error,
+            self.error_description.as_deref().unwrap_or_default(),
+            self.error_uri.as_deref().unwrap_or_default()
+        )
+    }
+}
+
+type Challenge = (String, Vec<(String, String)>);
+
+// Splits a `WWW-Authenticate` value into its challenges and their
+// attributes (RFC 9110 section 11.6.1). A name which is not followed by "="
+// starts a new challenge. token68 credentials are not supported.
+fn parse_challenges(header: &str) -> Vec<Challenge> {
+    let mut challenges: Vec<Challenge> = vec![];
+    let mut chars = header.chars().peekable();
+    loop {
+        while chars.next_if(|c| c.is_whitespace() || *c == ',').is_some() {}
+        if chars.peek().is_none() {
+            break;
+        }
+        let name = read_token(&mut chars);
+        if name.is_empty() {
+            // stray "=" or quote, skip it.
+            chars.next();
+            continue;
+        }
+        while chars.next_if(|c| c.is_whitespace()).is_some() {}
+        if chars.next_if_eq(&'=').is_none() {
+            challenges.push((name, vec![]));
+            continue;
+        }
+        while chars.next_if(|c| c.is_whitespace()).is_some() {}
+        let value = match chars.next_if_eq(&'"') {
+            Some(_) => read_quoted(&mut chars),
+            None => read_token(&mut chars),
+        };
+        if let Some((_, params)) = challenges.last_mut() {
+            params.push((name, value));
+        }
+    }
+    challenges
+}
+
+fn read_token(chars: &mut Peekable<Chars>) -> String {
+    let mut token = String::new();
+    while let Some(c) = chars.next_if(|c| !c.is_whitespace() && !matches!(c, ',' | '=' | '"')) {
+        token.push(c);
+    }
+    token
+}
+
+// reads up to the closing quote, which is consumed, unescaping "\x".
+fn read_quoted(chars: &mut Peekable<Chars>) -> String {
+    let mut value = String::new();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => break,
+            '\\' => value.extend(chars.next()),
+            c => value.push(c),
+        }
+    }
+    value
+}
+
+#[cfg(test)]
+mod bearer_error_tests {
+    use super::*;
+
+    #[test]
+    fn test_invalid_token() {
+        // RFC 6750 section 3 example.
+        let error = BearerError::from_www_authenticate(
+            r#"Bearer realm="example", error="invalid_token", error_description="The access token expired""#,
+        )
+        .unwrap();
+        assert_eq!(error.error(), &BearerErrorKind::InvalidToken);
+        assert_eq!(
+            error.error_description(),
+            Some("The access token expired".to_owned())
+        );
+        assert_eq!(error.error_uri(), None);
+    }
+
+    #[test]
+    fn test_insufficient_scope() {
+        let error = BearerError::from_www_authenticate(
+            r#"Basic realm="api", Bearer error=insufficient_scope, scope="openid \"email\"""#,
+        )
+        .unwrap();
+        assert_eq!(error.error(), &BearerErrorKind::InsufficientScope);
+        assert_eq!(error.scope(), Some(r#"openid "email""#.to_owned()));
+    }
+
+    #[test]
+    fn test_without_error() {
+        assert_eq!(
+            BearerError::from_www_authenticate(r#"Bearer realm="example""#),
+            None
+        );
+        assert_eq!(
+            BearerError::from_www_authenticate(r#"Basic realm="example", error="invalid_token""#),
+            None
+        );
+    }
+
+    #[test]
+    fn test_unknown_error_code() {
+        let error = BearerError::from_www_authenticate(r#"bearer error="use_dpop_nonce""#).unwrap();
+        assert_eq!(
+            error.error(),
+            &BearerErrorKind::Other("use_dpop_nonce".to_owned())
+        );
+    }
+}
@@ -0,0 +1,271 @@
+use crate::utils;
+use std::error::Error;
+use std::fmt::{Debug, Display};
+use std::sync::Arc;
+
+// Error of a ClientAssertion which could not produce an assertion, e.g.
+// because signing it failed. Holds the implementor's own error.
+#[derive(Debug)]
+pub struct ClientAssertionError(Box<dyn Error + Send + Sync>);
+
+impl ClientAssertionError {
+    pub fn new<E>(error: E) -> Self
+    where
+        E: Into<Box<dyn Error + Send + Sync>>,
+    {
+        Self(error.into())
+    }
+}
+
+impl Display for ClientAssertionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "ClientAssertionError({})", self.0)
+    }
+}
+
+impl Error for ClientAssertionError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(self.0.as_ref())
+    }
+}
+
+// Produces the `client_assertion_type` and `client_assertion` parameters for
+// assertion based client authentication (RFC 7521 section 4.2). Implementors
+// are asked for a fresh assertion every time a request is built.
+pub trait ClientAssertion: Debug + Send + Sync {
+    // the token_endpoint_auth_method name registered for this method, like
+    // "private_key_jwt". methods without a registered name return their
+    // assertion type, and are then not checked against server metadata.
+    fn get_auth_method(&self) -> String;
+
+    fn get_assertion_type(&self) -> String;
+
+    fn get_assertion(&self) -> Result<String, ClientAssertionError>;
+}
+
+// Assertion that was obtained out of band and is sent as is.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StaticClientAssertion {
+    auth_method: String,
+    assertion_type: String,
+    assertion: String,
+}
+
+impl StaticClientAssertion {
+    pub fn new(auth_method: String, assertion_type: String, assertion: String) -> Self {
+        Self {
+            auth_method,
+            assertion_type,
+            assertion,
+        }
+    }
+}
+
+impl ClientAssertion for StaticClientAssertion {
+    fn get_auth_method(&self) -> String {
+        self.auth_method.clone()
+    }
+
+    fn get_assertion_type(&self) -> String {
+        self.assertion_type.clone()
+    }
+
+    fn get_assertion(&self) -> Result<String, ClientAssertionError> {
+        Ok(self.assertion.clone())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum ClientAuth {
+    // public client which only identifies itself with its client_id.
+    None {
+        client_id: String,
+    },
+    // RFC 6749 section 2.3.1, credentials in the Authorization header.
+    ClientSecretBasic {
+        client_id: String,
+        client_secret: String,
+    },
+    // RFC 6749 section 2.3.1, credentials in the request body.
+    ClientSecretPost {
+        client_id: String,
+        client_secret: String,
+    },
+    // RFC 7521 section 4.2. The client_id is optional as it can be derived
+    // from the assertion.
+    Assertion {
+        client_id: Option<String>,
+        assertion: Arc<dyn ClientAssertion>,
+    },
+}
+
+impl ClientAuth {
+    pub fn get_auth_method(&self) -> String {
+        match self {
+            ClientAuth::None { .. } => "none".into(),
+            ClientAuth::ClientSecretBasic { .. } => "client_secret_basic".into(),
+            ClientAuth::ClientSecretPost { .. } => "client_secret_post".into(),
+            ClientAuth::Assertion { assertion, .. } => assertion.get_auth_method(),
+        }
+    }
+
+    pub fn get_client_id(&self) -> Option<String> {
+        match self {
+            ClientAuth::None { client_id }
+            | ClientAuth::ClientSecretBasic { client_id, .. }
+            | ClientAuth::ClientSecretPost { client_id, .. } => Some(client_id.clone()),
+            ClientAuth::Assertion { client_id, .. } => client_id.clone(),
+        }
+    }
+
+    pub fn get_headers(&self) -> Vec<(String, String)> {
+        match self {
+            ClientAuth::ClientSecretBasic {
+                client_id,
+                client_secret,
+            } => {
+                // the client id and secret are form-urlencoded before being
+                // joined and base64 encoded (RFC 6749 section 2.3.1).
+                let credentials = format!(
+                    "{}:{}",
+                    utils::form_urlencode(client_id),
+                    utils::form_urlencode(client_secret)
+                );
+                let encoded = data_encoding::BASE64.encode(credentials.as_bytes());
+                vec![("Authorization".into(), format!("Basic {encoded}"))]
+            }
+            _ => vec![],
+        }
+    }
+
+    // The `client_assertion` parameter is not part of these, as a new one is
+    // produced for every request sent, see get_client_assertion.
+    pub fn get_request_params_as_vec(&self) -> Vec<(String, String)> {
+        match self {
+            ClientAuth::None { client_id } => {
+                vec![("client_id".into(), client_id.clone())]
+            }
+            ClientAuth::ClientSecretBasic { .. } => vec![],
+            ClientAuth::ClientSecretPost {
+                client_id,
+                client_secret,
+            } => vec![
+                ("client_id".into(), client_id.clone()),
+                ("client_secret".into(), client_secret.clone()),
+            ],
+            ClientAuth::Assertion {
+                client_id,
+                assertion,
+            } => {
+                let mut params = vec![];
+                if let Some(client_id) = client_id {
+                    params.push(("client_id".into(), client_id.clone()));
+                }
+                params.push((
+                    "client_assertion_type".into(),
+                    assertion.get_assertion_type(),
+                ));
+                params
+            }
+        }
+    }
+
+    // A fresh `client_assertion` value, None for the methods which do not
+    // use one. execute adds it to the request body.
+    pub fn get_client_assertion(&self) -> Result<Option<String>, ClientAssertionError> {
+        match self {
+            ClientAuth::Assertion { assertion, .. } => assertion.get_assertion().map(Some),
+            _ => Ok(None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod client_auth_tests {
+    use super::*;
+
+    #[test]
+    fn test_client_secret_basic() {
+        let auth = ClientAuth::ClientSecretBasic {
+            client_id: "s6BhdRkqt3".into(),
+            client_secret: "gX1fBat3bV".into(),
+        };
+        assert_eq!(
+            auth.get_headers(),
+            vec![(
+                "Authorization".to_owned(),
+                "Basic czZCaGRSa3F0MzpnWDFmQmF0M2JW".to_owned()
+            )]
+        );
+        assert!(auth.get_request_params_as_vec().is_empty());
+        assert_eq!(auth.get_auth_method(), "client_secret_basic");
+    }
+
+    #[test]
+    fn test_client_secret_basic_encodes_credentials() {
+        let auth = ClientAuth::ClientSecretBasic {
+            client_id: "id:1".into(),
+            client_secret: "sé cret".into(),
+        };
+        let encoded = data_encoding::BASE64.encode(b"id%3A1:s%C3%A9+cret");
+        assert_eq!(
+            auth.get_headers(),
+            vec![("Authorization".to_owned(), format!("Basic {encoded}"))]
+        );
+    }
+
+    #[test]
+    fn test_client_secret_post() {
+        let auth = ClientAuth::ClientSecretPost {
+            client_id: "s6BhdRkqt3".into(),
+            client_secret: "gX1fBat3bV".into(),
+        };
+        assert!(auth.get_headers().is_empty());
+        assert_eq!(
+            auth.get_request_params_as_vec(),
+            vec![
+                ("client_id".to_owned(), "s6BhdRkqt3".to_owned()),
+                ("client_secret".to_owned(), "gX1fBat3bV".to_owned()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_none() {
+        let auth = ClientAuth::None {
+            client_id: "s6BhdRkqt3".into(),
+        };
+        assert!(auth.get_headers().is_empty());
+        assert_eq!(
+            auth.get_request_params_as_vec(),
+            vec![("client_id".to_owned(), "s6BhdRkqt3".to_owned())]
+        );
+        assert_eq!(auth.get_auth_method(), "none");
+    }
+
+    #[test]
+    fn test_assertion() {
+        let auth = ClientAuth::Assertion {
+            client_id: None,
+            assertion: Arc::new(StaticClientAssertion::new(
+                "private_key_jwt".into(),
+                "urn:ietf:params:oauth:client-assertion-type:jwt-bearer".into(),
+                "eyJhbGciOiJSUzI1NiJ9.e30.c2ln".into(),
+            )),
+        };
+        assert!(auth.get_headers().is_empty());
+        assert_eq!(
+            auth.get_request_params_as_vec(),
+            vec![(
+                "client_assertion_type".to_owned(),
+                "urn:ietf:params:oauth:client-assertion-type:jwt-bearer".to_owned()
+            )]
+        );
+        assert_eq!(
+            auth.get_client_assertion().unwrap(),
+            Some("eyJhbGciOiJSUzI1NiJ9.e30.c2ln".to_owned())
+        );
+        assert_eq!(auth.get_auth_method(), "private_key_jwt");
+        assert_eq!(auth.get_client_id(), None);
+    }
+}
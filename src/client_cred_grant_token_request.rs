@@ -1,9 +1,13 @@
+use crate::http::{AsyncHttpClient, HttpClient, RequestError};
 use crate::utils;
-use crate::OAuthParams;
+use crate::{ClientAuth, OAuthParams, Token};
+use serde::de::DeserializeOwned;
 
+#[derive(Default)]
 pub struct ClientCredentialsGrantAuthTokenRequest {
     extras: Option<Vec<(String, String)>>,
     scope: Option<Vec<String>>,
+    client_auth: Option<ClientAuth>,
 }
 
 impl OAuthParams for ClientCredentialsGrantAuthTokenRequest {
@@ -32,6 +36,10 @@ impl OAuthParams for ClientCredentialsGrantAuthTokenRequest {
     fn get_extra_params_ref(&self) -> Option<&Vec<(String, String)>> {
         self.extras.as_ref()
     }
+
+    fn get_client_auth(&self) -> Option<&ClientAuth> {
+        self.client_auth.as_ref()
+    }
 }
 
 impl ClientCredentialsGrantAuthTokenRequest {
@@ -39,9 +47,14 @@ impl ClientCredentialsGrantAuthTokenRequest {
         Self {
             extras: None,
             scope: None,
+            client_auth: None,
         }
     }
 
+    pub fn set_client_auth(&mut self, client_auth: ClientAuth) {
+        self.client_auth = Some(client_auth);
+    }
+
     pub fn add_scope(&mut self, scope: String) {
         utils::add_scope(self, scope);
     }
@@ -62,6 +75,77 @@ impl ClientCredentialsGrantAuthTokenRequest {
     }
 
     pub fn get_headers(&self) -> Vec<(String, String)> {
-        utils::get_headers()
+        utils::get_headers(self)
+    }
+
+    pub fn get_request_body(&self) -> Vec<u8> {
+        utils::get_request_body(self)
+    }
+
+    pub fn execute<C, T>(&self, client: &C, token_url: &str) -> Result<T, RequestError<C::Error>>
+    where
+        C: HttpClient,
+        T: Token + DeserializeOwned,
+    {
+        utils::execute_token_request(self, client, token_url)
+    }
+
+    pub async fn execute_async<C, T>(
+        &self,
+        client: &C,
+        token_url: &str,
+    ) -> Result<T, RequestError<C::Error>>
+    where
+        C: AsyncHttpClient,
+        T: Token + DeserializeOwned,
+    {
+        utils::execute_token_request_async(self, client, token_url).await
+    }
+}
+
+#[cfg(test)]
+mod client_cred_grant_token_request_tests {
+    use super::*;
+
+    #[test]
+    fn test_client_secret_basic() {
+        let mut request = ClientCredentialsGrantAuthTokenRequest::new();
+        request.set_client_auth(ClientAuth::ClientSecretBasic {
+            client_id: "s6BhdRkqt3".into(),
+            client_secret: "gX1fBat3bV".into(),
+        });
+
+        assert_eq!(
+            request.get_headers(),
+            vec![
+                (
+                    "Content-Type".to_owned(),
+                    "application/x-www-form-urlencoded".to_owned()
+                ),
+                (
+                    "Authorization".to_owned(),
+                    "Basic czZCaGRSa3F0MzpnWDFmQmF0M2JW".to_owned()
+                ),
+            ]
+        );
+        assert_eq!(
+            request.get_request_params_as_vec(),
+            vec![("grant_type".to_owned(), "client_credentials".to_owned())]
+        );
+    }
+
+    #[test]
+    fn test_client_secret_post() {
+        let mut request = ClientCredentialsGrantAuthTokenRequest::new();
+        request.set_client_auth(ClientAuth::ClientSecretPost {
+            client_id: "s6BhdRkqt3".into(),
+            client_secret: "gX1fBat3bV".into(),
+        });
+
+        assert_eq!(request.get_headers().len(), 1);
+        assert_eq!(
+            String::from_utf8(request.get_request_body()).unwrap(),
+            "grant_type=client_credentials&client_id=s6BhdRkqt3&client_secret=gX1fBat3bV"
+        );
     }
 }
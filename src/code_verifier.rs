@@ -1,32 +1,174 @@
-use crypto_hash;
-use data_encoding;
-use rand;
+use crate::random::Random;
+use crate::utils;
+use rand::{CryptoRng, RngCore};
+use std::error::Error;
+use std::fmt::Display;
+use std::str::FromStr;
 
-struct Random;
+// RFC 7636 section 4.1: the verifier is between 43 and 128 characters long.
+pub const CODE_VERIFIER_MIN_LEN: usize = 43;
+pub const CODE_VERIFIER_MAX_LEN: usize = 128;
 
-impl Random {
-    fn new() -> Self {
-        return Random {};
+#[derive(Debug, PartialEq, Eq)]
+pub enum CodeVerifierError {
+    InvalidLength(usize),
+    InvalidCharacter(char),
+}
+
+impl Display for CodeVerifierError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CodeVerifierError::InvalidLength(len) => {
+                write!(f, "CodeVerifierError::InvalidLength({len})")
+            }
+            CodeVerifierError::InvalidCharacter(c) => {
+                write!(f, "CodeVerifierError::InvalidCharacter({c:?})")
+            }
+        }
     }
+}
 
-    fn get_octet_sequence(&self, len: u8) -> Vec<u8> {
-        (0..len).map(|_| rand::random::<u8>()).collect::<Vec<_>>()
+impl Error for CodeVerifierError {}
+
+fn is_unreserved(c: char) -> bool {
+    c.is_ascii_alphanumeric() || matches!(c, '-' | '.' | '_' | '~')
+}
+
+fn check_length(len: usize) -> Result<(), CodeVerifierError> {
+    if (CODE_VERIFIER_MIN_LEN..=CODE_VERIFIER_MAX_LEN).contains(&len) {
+        Ok(())
+    } else {
+        Err(CodeVerifierError::InvalidLength(len))
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ChallengMethod {
     Plain,
     Sha256,
 }
 
+impl ChallengMethod {
+    // the code_challenge_method value as spelled by RFC 7636 section 4.3.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ChallengMethod::Plain => "plain",
+            ChallengMethod::Sha256 => "S256",
+        }
+    }
+}
+
+impl FromStr for ChallengMethod {
+    type Err = PkceVerificationError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "plain" => Ok(ChallengMethod::Plain),
+            "S256" => Ok(ChallengMethod::Sha256),
+            _ => Err(PkceVerificationError::UnsupportedMethod(s.to_owned())),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PkcePolicy {
+    #[default]
+    AllowPlain,
+    // refuse the plain method, as recommended by RFC 7636 section 7.2 for
+    // servers which do not need to support constrained clients.
+    Strict,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum PkceVerificationError {
+    InvalidVerifier(CodeVerifierError),
+    UnsupportedMethod(String),
+    PlainNotAllowed,
+    Mismatch,
+}
+
+impl Display for PkceVerificationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        use PkceVerificationError::*;
+        match self {
+            InvalidVerifier(e) => write!(f, "PkceVerificationError::InvalidVerifier({e})"),
+            UnsupportedMethod(m) => write!(f, "PkceVerificationError::UnsupportedMethod({m})"),
+            PlainNotAllowed => write!(f, "PkceVerificationError::PlainNotAllowed"),
+            Mismatch => write!(f, "PkceVerificationError::Mismatch"),
+        }
+    }
+}
+
+impl Error for PkceVerificationError {}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct CodeVerifier(String);
 
+impl Default for CodeVerifier {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl CodeVerifier {
     pub fn new() -> Self {
-        let unreserved = Random::new();
-        let octet_seq = unreserved.get_octet_sequence(32);
-        let encoded = data_encoding::BASE64URL.encode(&octet_seq);
-        return Self(encoded);
+        Self::generate(&mut rand::thread_rng(), CODE_VERIFIER_MIN_LEN)
+    }
+
+    pub fn with_length(len: usize) -> Result<Self, CodeVerifierError> {
+        Self::with_rng(&mut rand::thread_rng(), len)
+    }
+
+    pub fn with_rng<R>(rng: &mut R, len: usize) -> Result<Self, CodeVerifierError>
+    where
+        R: RngCore + CryptoRng,
+    {
+        check_length(len)?;
+        Ok(Self::generate(rng, len))
+    }
+
+    pub fn from_string(verifier: String) -> Result<Self, CodeVerifierError> {
+        check_length(verifier.len())?;
+        match verifier.chars().find(|c| !is_unreserved(*c)) {
+            Some(c) => Err(CodeVerifierError::InvalidCharacter(c)),
+            None => Ok(Self(verifier)),
+        }
+    }
+
+    // server side check of RFC 7636 section 4.6: recompute the challenge
+    // from the presented verifier and compare it with the stored one.
+    pub fn verify(
+        code_challenge: &str,
+        challenge_method: &str,
+        code_verifier: &str,
+        policy: PkcePolicy,
+    ) -> Result<(), PkceVerificationError> {
+        let method = challenge_method.parse::<ChallengMethod>()?;
+        if method == ChallengMethod::Plain && policy == PkcePolicy::Strict {
+            return Err(PkceVerificationError::PlainNotAllowed);
+        }
+        let verifier = Self::from_string(code_verifier.to_owned())
+            .map_err(PkceVerificationError::InvalidVerifier)?;
+        let expected = verifier.get_code_challange(method);
+        if utils::constant_time_eq(expected.as_bytes(), code_challenge.as_bytes()) {
+            Ok(())
+        } else {
+            Err(PkceVerificationError::Mismatch)
+        }
+    }
+
+    // every base64url character is an unreserved character, so encoding
+    // enough random octets and cutting the result down to the requested
+    // length gives a valid verifier with ~6 bits of entropy per character.
+    fn generate<R>(rng: &mut R, len: usize) -> Self
+    where
+        R: RngCore + CryptoRng,
+    {
+        let mut unreserved = Random::new(rng);
+        let octet_seq = unreserved.get_octet_sequence(len.div_ceil(4) * 3);
+        let mut encoded = data_encoding::BASE64URL_NOPAD.encode(&octet_seq);
+        encoded.truncate(len);
+        Self(encoded)
     }
 
     pub fn get_code_verifier(&self) -> String {
@@ -37,9 +179,8 @@ impl CodeVerifier {
         match challenge_method {
             ChallengMethod::Plain => self.0.clone(),
             ChallengMethod::Sha256 => {
-                let hash = crypto_hash::digest(crypto_hash::Algorithm::SHA256, &self.0.as_bytes());
-                let encoded = data_encoding::BASE64URL.encode(&hash);
-                encoded
+                let hash = crypto_hash::digest(crypto_hash::Algorithm::SHA256, self.0.as_bytes());
+                data_encoding::BASE64URL_NOPAD.encode(&hash)
             }
         }
     }
@@ -48,20 +189,76 @@ impl CodeVerifier {
 #[cfg(test)]
 mod tests {
     use super::*;
-    #[test]
-    fn test_get_octect_sequence() {
-        let unreserved = Random::new();
-        let res = unreserved.get_octet_sequence(43);
-        res.iter().for_each(|c| print!("{}", c));
-        println!("");
-    }
-
     #[test]
     fn test_get_code_verifier() {
         let verifier = CodeVerifier::new();
         let code_verifier = verifier.get_code_verifier();
         println!("{code_verifier}");
-        assert_eq!(code_verifier.len(), 44);
+        assert_eq!(code_verifier.len(), 43);
+        assert!(code_verifier.chars().all(is_unreserved));
+    }
+
+    #[test]
+    fn test_with_length() {
+        for len in [43, 44, 45, 46, 64, 127, 128] {
+            let verifier = CodeVerifier::with_length(len).unwrap();
+            let code_verifier = verifier.get_code_verifier();
+            assert_eq!(code_verifier.len(), len);
+            assert!(code_verifier.chars().all(is_unreserved));
+        }
+    }
+
+    #[test]
+    fn test_with_length_out_of_range() {
+        assert_eq!(
+            CodeVerifier::with_length(42),
+            Err(CodeVerifierError::InvalidLength(42))
+        );
+        assert_eq!(
+            CodeVerifier::with_length(129),
+            Err(CodeVerifierError::InvalidLength(129))
+        );
+    }
+
+    #[test]
+    fn test_with_rng_is_deterministic() {
+        use rand::rngs::StdRng;
+        use rand::SeedableRng;
+
+        let first = CodeVerifier::with_rng(&mut StdRng::seed_from_u64(7), 64).unwrap();
+        let second = CodeVerifier::with_rng(&mut StdRng::seed_from_u64(7), 64).unwrap();
+        let other = CodeVerifier::with_rng(&mut StdRng::seed_from_u64(8), 64).unwrap();
+        assert_eq!(first, second);
+        assert_ne!(first, other);
+    }
+
+    #[test]
+    fn test_from_string() {
+        let valid = "abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789-._~";
+        assert_eq!(
+            CodeVerifier::from_string(valid.into()).map(|v| v.get_code_verifier()),
+            Ok(String::from(valid))
+        );
+        assert_eq!(
+            CodeVerifier::from_string("a".repeat(42)),
+            Err(CodeVerifierError::InvalidLength(42))
+        );
+        assert_eq!(
+            CodeVerifier::from_string(format!("{}=", "a".repeat(42))),
+            Err(CodeVerifierError::InvalidCharacter('='))
+        );
+        assert_eq!(
+            CodeVerifier::from_string(format!("{}+/", "a".repeat(42))),
+            Err(CodeVerifierError::InvalidCharacter('+'))
+        );
+    }
+
+    #[test]
+    fn test_get_code_challenge_sha256_is_unpadded() {
+        let verifier = CodeVerifier::new();
+        let challenge = verifier.get_code_challange(ChallengMethod::Sha256);
+        assert_eq!(challenge.len(), 43);
+        assert!(challenge.chars().all(is_unreserved));
     }
 
     #[test]
@@ -77,4 +274,104 @@ mod tests {
         let challenge = verifier.get_code_challange(ChallengMethod::Sha256);
         println!("{challenge}");
     }
+
+    #[test]
+    fn test_get_code_challenge_rfc7636_appendix_b() {
+        let verifier =
+            CodeVerifier::from_string("dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXk".into())
+                .unwrap();
+        assert_eq!(
+            verifier.get_code_challange(ChallengMethod::Sha256),
+            "E9Melhoa2OwvFrEMTJguCHaoeK1t8URWbuGJSstw-cM"
+        );
+        assert_eq!(
+            verifier.get_code_challange(ChallengMethod::Plain),
+            "dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXk"
+        );
+    }
+
+    #[test]
+    fn test_challenge_method_as_str() {
+        assert_eq!(ChallengMethod::Plain.as_str(), "plain");
+        assert_eq!(ChallengMethod::Sha256.as_str(), "S256");
+    }
+
+    const RFC7636_VERIFIER: &str = "dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXk";
+    const RFC7636_CHALLENGE: &str = "E9Melhoa2OwvFrEMTJguCHaoeK1t8URWbuGJSstw-cM";
+
+    #[test]
+    fn test_verify_s256() {
+        assert_eq!(
+            CodeVerifier::verify(
+                RFC7636_CHALLENGE,
+                "S256",
+                RFC7636_VERIFIER,
+                PkcePolicy::Strict
+            ),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn test_verify_mismatch() {
+        let other = CodeVerifier::new().get_code_verifier();
+        assert_eq!(
+            CodeVerifier::verify(RFC7636_CHALLENGE, "S256", &other, PkcePolicy::Strict),
+            Err(PkceVerificationError::Mismatch)
+        );
+        assert_eq!(
+            CodeVerifier::verify(
+                RFC7636_CHALLENGE,
+                "plain",
+                RFC7636_VERIFIER,
+                PkcePolicy::AllowPlain
+            ),
+            Err(PkceVerificationError::Mismatch)
+        );
+    }
+
+    #[test]
+    fn test_verify_plain() {
+        assert_eq!(
+            CodeVerifier::verify(
+                RFC7636_VERIFIER,
+                "plain",
+                RFC7636_VERIFIER,
+                PkcePolicy::AllowPlain
+            ),
+            Ok(())
+        );
+        assert_eq!(
+            CodeVerifier::verify(
+                RFC7636_VERIFIER,
+                "plain",
+                RFC7636_VERIFIER,
+                PkcePolicy::Strict
+            ),
+            Err(PkceVerificationError::PlainNotAllowed)
+        );
+    }
+
+    #[test]
+    fn test_verify_unknown_method() {
+        assert_eq!(
+            CodeVerifier::verify(
+                RFC7636_CHALLENGE,
+                "s256",
+                RFC7636_VERIFIER,
+                PkcePolicy::AllowPlain
+            ),
+            Err(PkceVerificationError::UnsupportedMethod("s256".into()))
+        );
+    }
+
+    #[test]
+    fn test_verify_malformed_verifier() {
+        assert_eq!(
+            CodeVerifier::verify(RFC7636_CHALLENGE, "S256", "short", PkcePolicy::Strict),
+            Err(PkceVerificationError::InvalidVerifier(
+                CodeVerifierError::InvalidLength(5)
+            ))
+        );
+    }
 }
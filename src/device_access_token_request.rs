@@ -0,0 +1,313 @@
+use crate::http::{AsyncHttpClient, HttpClient, RequestError};
+use crate::utils;
+use crate::{AuthTokenErrorKind, ClientAuth, DeviceAuthorizationResponse, OAuthParams, Token};
+use serde::de::DeserializeOwned;
+use std::future::Future;
+use std::time::Duration;
+
+pub const DEVICE_CODE_GRANT_TYPE: &str = "urn:ietf:params:oauth:grant-type:device_code";
+
+// RFC 8628 section 3.5: slow_down increases the interval by 5 seconds.
+const SLOW_DOWN_INCREMENT_SECS: u64 = 5;
+
+enum PollAction {
+    Continue,
+    SlowDown,
+    Stop,
+}
+
+fn get_poll_action<T, E>(res: &Result<T, RequestError<E>>) -> PollAction {
+    match res {
+        Err(RequestError::ServerResponse(e)) => match e.error() {
+            AuthTokenErrorKind::AuthorizationPending => PollAction::Continue,
+            AuthTokenErrorKind::SlowDown => PollAction::SlowDown,
+            _ => PollAction::Stop,
+        },
+        _ => PollAction::Stop,
+    }
+}
+
+// Device Access Token Request (RFC 8628 section 3.4).
+#[derive(Debug)]
+pub struct DeviceAccessTokenRequest {
+    device_code: String,
+    extras: Option<Vec<(String, String)>>,
+    // also holds the client_id, which is only sent from here.
+    client_auth: ClientAuth,
+}
+
+impl OAuthParams for DeviceAccessTokenRequest {
+    fn get_grant_type(&self) -> Option<String> {
+        DEVICE_CODE_GRANT_TYPE.to_owned().into()
+    }
+
+    fn get_device_code(&self) -> Option<String> {
+        Some(self.device_code.clone())
+    }
+
+    fn get_extra_params_mut(&mut self) -> Option<&mut Vec<(String, String)>> {
+        if self.extras.is_none() {
+            self.extras = Some(vec![])
+        }
+        self.extras.as_mut()
+    }
+
+    fn get_extra_params_ref(&self) -> Option<&Vec<(String, String)>> {
+        self.extras.as_ref()
+    }
+
+    fn get_client_auth(&self) -> Option<&ClientAuth> {
+        Some(&self.client_auth)
+    }
+}
+
+impl DeviceAccessTokenRequest {
+    pub fn new(device_code: String, client_id: String) -> Self {
+        Self {
+            device_code,
+            extras: None,
+            client_auth: ClientAuth::None { client_id },
+        }
+    }
+
+    // replaces the client authentication, including the client_id given to
+    // `new`, so the request never carries two different client ids.
+    pub fn set_client_auth(&mut self, client_auth: ClientAuth) {
+        self.client_auth = client_auth;
+    }
+
+    pub fn add_extra_param(&mut self, key: String, value: String) {
+        utils::add_extra_param(self, key, value);
+    }
+
+    pub fn get_request_params_as_vec(&self) -> Vec<(String, String)> {
+        utils::get_request_params_as_vec(self)
+    }
+
+    pub fn get_headers(&self) -> Vec<(String, String)> {
+        utils::get_headers(self)
+    }
+
+    pub fn get_request_body(&self) -> Vec<u8> {
+        utils::get_request_body(self)
+    }
+
+    pub fn execute<C, T>(&self, client: &C, token_url: &str) -> Result<T, RequestError<C::Error>>
+    where
+        C: HttpClient,
+        T: Token + DeserializeOwned,
+    {
+        utils::execute_token_request(self, client, token_url)
+    }
+
+    pub async fn execute_async<C, T>(
+        &self,
+        client: &C,
+        token_url: &str,
+    ) -> Result<T, RequestError<C::Error>>
+    where
+        C: AsyncHttpClient,
+        T: Token + DeserializeOwned,
+    {
+        utils::execute_token_request_async(self, client, token_url).await
+    }
+
+    // Polls the token endpoint until the user completes the authorization,
+    // waiting `interval` seconds between attempts with the given sleep
+    // function (e.g. std::thread::sleep). Gives up with the last error once
+    // the device code has expired, counting from when device_auth was issued.
+    pub fn poll<C, T, S>(
+        &self,
+        client: &C,
+        token_url: &str,
+        device_auth: &DeviceAuthorizationResponse,
+        mut sleep: S,
+    ) -> Result<T, RequestError<C::Error>>
+    where
+        C: HttpClient,
+        T: Token + DeserializeOwned,
+        S: FnMut(Duration),
+    {
+        let mut interval = device_auth.interval();
+        let mut waited = device_auth.elapsed_secs();
+        loop {
+            sleep(Duration::from_secs(interval));
+            waited += interval;
+            let res = self.execute(client, token_url);
+            match get_poll_action(&res) {
+                PollAction::Stop => return res,
+                _ if waited >= device_auth.expires_in() => return res,
+                PollAction::SlowDown => interval += SLOW_DOWN_INCREMENT_SECS,
+                PollAction::Continue => {}
+            }
+        }
+    }
+
+    // Same as poll, with a sleep function returning a future (e.g.
+    // tokio::time::sleep).
+    pub async fn poll_async<C, T, S, F>(
+        &self,
+        client: &C,
+        token_url: &str,
+        device_auth: &DeviceAuthorizationResponse,
+        mut sleep: S,
+    ) -> Result<T, RequestError<C::Error>>
+    where
+        C: AsyncHttpClient,
+        T: Token + DeserializeOwned,
+        S: FnMut(Duration) -> F,
+        F: Future<Output = ()>,
+    {
+        let mut interval = device_auth.interval();
+        let mut waited = device_auth.elapsed_secs();
+        loop {
+            sleep(Duration::from_secs(interval)).await;
+            waited += interval;
+            let res = self.execute_async(client, token_url).await;
+            match get_poll_action(&res) {
+                PollAction::Stop => return res,
+                _ if waited >= device_auth.expires_in() => return res,
+                PollAction::SlowDown => interval += SLOW_DOWN_INCREMENT_SECS,
+                PollAction::Continue => {}
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod device_access_token_request_tests {
+    use super::*;
+    use crate::http::block_on;
+    use crate::{HttpResponse, MockHttpClient, TokenResponse};
+    use std::time::SystemTime;
+
+    const TOKEN: &str = r#"{"access_token": "2YotnFZFEjr1zCsicMWpAA", "token_type": "Bearer"}"#;
+
+    fn error_response(error: &str) -> HttpResponse {
+        HttpResponse::new(400, format!(r#"{{"error": "{error}"}}"#).into())
+    }
+
+    fn device_auth(expires_in: u64, interval: u64) -> DeviceAuthorizationResponse {
+        serde_json::from_value(serde_json::json!({
+            "device_code": "GmRhmhcxhwAzkoEqiMEg_DnyEysNkuNhszIySk9eS",
+            "user_code": "WDJB-MJHT",
+            "verification_uri": "https://example.com/device",
+            "expires_in": expires_in,
+            "interval": interval,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_request_params() {
+        let request = DeviceAccessTokenRequest::new("dc".into(), "1406020730".into());
+        assert_eq!(
+            request.get_request_params_as_vec(),
+            vec![
+                ("grant_type".to_owned(), DEVICE_CODE_GRANT_TYPE.to_owned()),
+                ("client_id".to_owned(), "1406020730".to_owned()),
+                ("device_code".to_owned(), "dc".to_owned()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_poll_pending_then_slow_down() {
+        let client = MockHttpClient::new();
+        client.push_response(error_response("authorization_pending"));
+        client.push_response(error_response("slow_down"));
+        client.push_response(error_response("authorization_pending"));
+        client.push_response(HttpResponse::new(200, TOKEN.into()));
+        let request = DeviceAccessTokenRequest::new("dc".into(), "1406020730".into());
+
+        let mut sleeps = vec![];
+        let token: TokenResponse = request
+            .poll(
+                &client,
+                "https://example.com/token",
+                &device_auth(1800, 5),
+                |d| sleeps.push(d.as_secs()),
+            )
+            .unwrap();
+        assert_eq!(token.access_token(), "2YotnFZFEjr1zCsicMWpAA");
+        assert_eq!(sleeps, vec![5, 5, 10, 10]);
+        assert_eq!(client.get_requests().len(), 4);
+    }
+
+    #[test]
+    fn test_poll_expired_token() {
+        let client = MockHttpClient::new();
+        client.push_response(error_response("authorization_pending"));
+        client.push_response(error_response("expired_token"));
+        let request = DeviceAccessTokenRequest::new("dc".into(), "1406020730".into());
+
+        let res: Result<TokenResponse, _> = request.poll(
+            &client,
+            "https://example.com/token",
+            &device_auth(1800, 5),
+            |_| {},
+        );
+        match res {
+            Err(RequestError::ServerResponse(e)) => {
+                assert!(matches!(e.error(), AuthTokenErrorKind::ExpiredToken))
+            }
+            res => panic!("unexpected result: {res:?}"),
+        }
+    }
+
+    #[test]
+    fn test_poll_stops_when_device_code_expires() {
+        let client = MockHttpClient::new();
+        for _ in 0..5 {
+            client.push_response(error_response("authorization_pending"));
+        }
+        let request = DeviceAccessTokenRequest::new("dc".into(), "1406020730".into());
+
+        let res: Result<TokenResponse, _> = request.poll(
+            &client,
+            "https://example.com/token",
+            &device_auth(15, 5),
+            |_| {},
+        );
+        assert!(matches!(res, Err(RequestError::ServerResponse(_))));
+        assert_eq!(client.get_requests().len(), 3);
+    }
+
+    #[test]
+    fn test_poll_counts_time_since_issue() {
+        let client = MockHttpClient::new();
+        for _ in 0..5 {
+            client.push_response(error_response("authorization_pending"));
+        }
+        let request = DeviceAccessTokenRequest::new("dc".into(), "1406020730".into());
+        let mut device_auth = device_auth(15, 5);
+        device_auth.issued_at = SystemTime::now() - Duration::from_secs(10);
+
+        let res: Result<TokenResponse, _> =
+            request.poll(&client, "https://example.com/token", &device_auth, |_| {});
+        assert!(matches!(res, Err(RequestError::ServerResponse(_))));
+        assert_eq!(client.get_requests().len(), 1);
+    }
+
+    #[test]
+    fn test_poll_async() {
+        let client = MockHttpClient::new();
+        client.push_response(error_response("slow_down"));
+        client.push_response(HttpResponse::new(200, TOKEN.into()));
+        let request = DeviceAccessTokenRequest::new("dc".into(), "1406020730".into());
+        let device_auth = device_auth(1800, 5);
+
+        let mut sleeps = vec![];
+        let res = block_on(request.poll_async::<_, TokenResponse, _, _>(
+            &client,
+            "https://example.com/token",
+            &device_auth,
+            |d| {
+                sleeps.push(d.as_secs());
+                std::future::ready(())
+            },
+        ));
+        assert!(res.is_ok());
+        assert_eq!(sleeps, vec![5, 10]);
+    }
+}
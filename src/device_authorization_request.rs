@@ -0,0 +1,232 @@
+use crate::http::{AsyncHttpClient, HttpClient, RequestError};
+use crate::utils;
+use crate::{ClientAuth, OAuthParams};
+use serde::{self, Deserialize};
+use std::time::SystemTime;
+
+// RFC 8628 section 3.2: the client polls every 5 seconds unless the server
+// says otherwise.
+const DEFAULT_INTERVAL_SECS: u64 = 5;
+
+fn default_interval() -> u64 {
+    DEFAULT_INTERVAL_SECS
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct DeviceAuthorizationResponse {
+    device_code: String,
+    user_code: String,
+    // some providers still use the draft name verification_url.
+    #[serde(alias = "verification_url")]
+    verification_uri: String,
+    verification_uri_complete: Option<String>,
+    expires_in: u64,
+    #[serde(default = "default_interval")]
+    interval: u64,
+    // expires_in counts from when the response was received, not from when
+    // polling starts.
+    #[serde(skip, default = "SystemTime::now")]
+    pub(crate) issued_at: SystemTime,
+}
+
+impl DeviceAuthorizationResponse {
+    pub fn device_code(&self) -> String {
+        self.device_code.clone()
+    }
+
+    pub fn user_code(&self) -> String {
+        self.user_code.clone()
+    }
+
+    pub fn verification_uri(&self) -> String {
+        self.verification_uri.clone()
+    }
+
+    pub fn verification_uri_complete(&self) -> Option<String> {
+        self.verification_uri_complete.clone()
+    }
+
+    pub fn expires_in(&self) -> u64 {
+        self.expires_in
+    }
+
+    pub fn interval(&self) -> u64 {
+        self.interval
+    }
+
+    pub fn issued_at(&self) -> SystemTime {
+        self.issued_at
+    }
+
+    // Seconds since the response was received, 0 if the clock went backwards.
+    pub(crate) fn elapsed_secs(&self) -> u64 {
+        self.issued_at.elapsed().map(|d| d.as_secs()).unwrap_or(0)
+    }
+}
+
+// Device Authorization Request (RFC 8628 section 3.1).
+#[derive(Debug)]
+pub struct DeviceAuthorizationRequest {
+    scope: Option<Vec<String>>,
+    extras: Option<Vec<(String, String)>>,
+    // also holds the client_id, which is only sent from here.
+    client_auth: ClientAuth,
+}
+
+impl OAuthParams for DeviceAuthorizationRequest {
+    fn get_scopes_mut(&mut self) -> Option<&mut Vec<String>> {
+        if self.scope.is_none() {
+            self.scope = Some(vec![])
+        }
+        self.scope.as_mut()
+    }
+
+    fn get_scopes_ref(&self) -> Option<&Vec<String>> {
+        self.scope.as_ref()
+    }
+
+    fn get_extra_params_mut(&mut self) -> Option<&mut Vec<(String, String)>> {
+        if self.extras.is_none() {
+            self.extras = Some(vec![])
+        }
+        self.extras.as_mut()
+    }
+
+    fn get_extra_params_ref(&self) -> Option<&Vec<(String, String)>> {
+        self.extras.as_ref()
+    }
+
+    fn get_client_auth(&self) -> Option<&ClientAuth> {
+        Some(&self.client_auth)
+    }
+}
+
+impl DeviceAuthorizationRequest {
+    pub fn new(client_id: String) -> Self {
+        Self {
+            scope: None,
+            extras: None,
+            client_auth: ClientAuth::None { client_id },
+        }
+    }
+
+    // replaces the client authentication, including the client_id given to
+    // `new`, so the request never carries two different client ids.
+    pub fn set_client_auth(&mut self, client_auth: ClientAuth) {
+        self.client_auth = client_auth;
+    }
+
+    pub fn add_scope(&mut self, scope: String) {
+        utils::add_scope(self, scope);
+    }
+
+    pub fn add_scopes<I>(&mut self, scopes: I)
+    where
+        I: IntoIterator<Item = String>,
+    {
+        utils::add_scopes(self, scopes);
+    }
+
+    pub fn add_extra_param(&mut self, key: String, value: String) {
+        utils::add_extra_param(self, key, value);
+    }
+
+    pub fn get_request_params_as_vec(&self) -> Vec<(String, String)> {
+        utils::get_request_params_as_vec(self)
+    }
+
+    pub fn get_headers(&self) -> Vec<(String, String)> {
+        utils::get_headers(self)
+    }
+
+    pub fn get_request_body(&self) -> Vec<u8> {
+        utils::get_request_body(self)
+    }
+
+    pub fn execute<C>(
+        &self,
+        client: &C,
+        device_authorization_url: &str,
+    ) -> Result<DeviceAuthorizationResponse, RequestError<C::Error>>
+    where
+        C: HttpClient,
+    {
+        utils::execute_request(self, client, device_authorization_url)
+    }
+
+    pub async fn execute_async<C>(
+        &self,
+        client: &C,
+        device_authorization_url: &str,
+    ) -> Result<DeviceAuthorizationResponse, RequestError<C::Error>>
+    where
+        C: AsyncHttpClient,
+    {
+        utils::execute_request_async(self, client, device_authorization_url).await
+    }
+}
+
+#[cfg(test)]
+mod device_authorization_request_tests {
+    use super::*;
+    use crate::{HttpResponse, MockHttpClient};
+
+    #[test]
+    fn test_request_params() {
+        let mut request = DeviceAuthorizationRequest::new("1406020730".into());
+        request.add_scopes(["example_scope".to_owned()]);
+        assert_eq!(
+            String::from_utf8(request.get_request_body()).unwrap(),
+            "client_id=1406020730&scope=example_scope"
+        );
+    }
+
+    #[test]
+    fn test_execute() {
+        let client = MockHttpClient::new();
+        client.push_response(HttpResponse::new(
+            200,
+            r#"{
+                "device_code": "GmRhmhcxhwAzkoEqiMEg_DnyEysNkuNhszIySk9eS",
+                "user_code": "WDJB-MJHT",
+                "verification_uri": "https://example.com/device",
+                "verification_uri_complete": "https://example.com/device?user_code=WDJB-MJHT",
+                "expires_in": 1800
+            }"#
+            .into(),
+        ));
+        let request = DeviceAuthorizationRequest::new("1406020730".into());
+
+        let response = request
+            .execute(&client, "https://example.com/device_authorization")
+            .unwrap();
+        assert_eq!(
+            response.device_code(),
+            "GmRhmhcxhwAzkoEqiMEg_DnyEysNkuNhszIySk9eS"
+        );
+        assert_eq!(response.user_code(), "WDJB-MJHT");
+        assert_eq!(response.verification_uri(), "https://example.com/device");
+        assert_eq!(
+            response.verification_uri_complete(),
+            Some("https://example.com/device?user_code=WDJB-MJHT".into())
+        );
+        assert_eq!(response.expires_in(), 1800);
+        assert_eq!(response.interval(), 5);
+    }
+
+    #[test]
+    fn test_response_verification_url_alias() {
+        let response: DeviceAuthorizationResponse = serde_json::from_str(
+            r#"{
+                "device_code": "dc",
+                "user_code": "uc",
+                "verification_url": "https://www.google.com/device",
+                "expires_in": 1800,
+                "interval": 10
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(response.verification_uri(), "https://www.google.com/device");
+        assert_eq!(response.interval(), 10);
+    }
+}
@@ -0,0 +1,80 @@
+use super::{AsyncHttpClient, HttpClient, HttpRequest, HttpResponse};
+use std::collections::VecDeque;
+use std::error::Error;
+use std::fmt::Display;
+use std::future::Future;
+use std::sync::Mutex;
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct MockHttpClientError;
+
+impl Display for MockHttpClientError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "MockHttpClientError(no response queued)")
+    }
+}
+
+impl Error for MockHttpClientError {}
+
+// In-memory transport which answers requests from a queue of canned
+// responses and records every request it was asked to send.
+#[derive(Debug, Default)]
+pub struct MockHttpClient {
+    responses: Mutex<VecDeque<HttpResponse>>,
+    requests: Mutex<Vec<HttpRequest>>,
+}
+
+impl MockHttpClient {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push_response(&self, response: HttpResponse) {
+        self.responses.lock().unwrap().push_back(response);
+    }
+
+    pub fn get_requests(&self) -> Vec<HttpRequest> {
+        self.requests.lock().unwrap().clone()
+    }
+
+    fn respond(&self, request: HttpRequest) -> Result<HttpResponse, MockHttpClientError> {
+        self.requests.lock().unwrap().push(request);
+        self.responses
+            .lock()
+            .unwrap()
+            .pop_front()
+            .ok_or(MockHttpClientError)
+    }
+}
+
+impl HttpClient for MockHttpClient {
+    type Error = MockHttpClientError;
+
+    fn execute(&self, request: HttpRequest) -> Result<HttpResponse, Self::Error> {
+        self.respond(request)
+    }
+}
+
+impl AsyncHttpClient for MockHttpClient {
+    type Error = MockHttpClientError;
+
+    fn execute(
+        &self,
+        request: HttpRequest,
+    ) -> impl Future<Output = Result<HttpResponse, Self::Error>> + Send {
+        std::future::ready(self.respond(request))
+    }
+}
+
+// Drives a future to completion by busy polling. The mock transport never
+// returns Pending, so tests of the async paths need no runtime.
+#[cfg(test)]
+pub(crate) fn block_on<F: Future>(fut: F) -> F::Output {
+    let mut fut = std::pin::pin!(fut);
+    let mut cx = std::task::Context::from_waker(std::task::Waker::noop());
+    loop {
+        if let std::task::Poll::Ready(v) = fut.as_mut().poll(&mut cx) {
+            return v;
+        }
+    }
+}
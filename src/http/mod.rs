@@ -0,0 +1,270 @@
+mod mock;
+#[cfg(feature = "reqwest")]
+mod reqwest_client;
+#[cfg(feature = "ureq")]
+mod ureq_client;
+
+use crate::{AuthTokenError, ClientAssertionError};
+use serde::de::DeserializeOwned;
+use std::error::Error;
+use std::fmt::Display;
+use std::future::Future;
+
+#[cfg(test)]
+pub(crate) use mock::block_on;
+pub use mock::{MockHttpClient, MockHttpClientError};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HttpMethod {
+    Get,
+    Post,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HttpRequest {
+    pub method: HttpMethod,
+    pub url: String,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HttpResponse {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+impl HttpResponse {
+    pub fn new(status: u16, body: Vec<u8>) -> Self {
+        Self {
+            status,
+            headers: vec![],
+            body,
+        }
+    }
+
+    pub fn get_header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+pub trait HttpClient {
+    type Error: Error + 'static;
+
+    fn execute(&self, request: HttpRequest) -> Result<HttpResponse, Self::Error>;
+}
+
+pub trait AsyncHttpClient {
+    type Error: Error + 'static;
+
+    fn execute(
+        &self,
+        request: HttpRequest,
+    ) -> impl Future<Output = Result<HttpResponse, Self::Error>> + Send;
+}
+
+#[derive(Debug)]
+pub enum RequestError<E> {
+    // the http client failed to send the request or receive the response.
+    Transport(E),
+    // the authorization server responded with an error (RFC 6749 section 5.2).
+    ServerResponse(AuthTokenError),
+    // the response body could not be parsed.
+    Parse(serde_json::Error),
+    // the response status is neither success nor an OAuth error response.
+    UnexpectedStatus(u16, Vec<u8>),
+    // the client assertion for the request could not be produced, so
+    // nothing was sent.
+    ClientAssertion(ClientAssertionError),
+}
+
+impl<E> Display for RequestError<E>
+where
+    E: Display,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        use RequestError::*;
+        match self {
+            Transport(e) => write!(f, "RequestError::Transport({e})"),
+            ServerResponse(e) => write!(f, "RequestError::ServerResponse({e})"),
+            Parse(e) => write!(f, "RequestError::Parse({e})"),
+            UnexpectedStatus(status, body) => write!(
+                f,
+                "RequestError::UnexpectedStatus(status = {}, body = {})",
+                status,
+                String::from_utf8_lossy(body)
+            ),
+            ClientAssertion(e) => write!(f, "RequestError::ClientAssertion({e})"),
+        }
+    }
+}
+
+impl<E> Error for RequestError<E>
+where
+    E: Error + 'static,
+{
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        use RequestError::*;
+        match self {
+            Transport(e) => Some(e),
+            ServerResponse(e) => Some(e),
+            Parse(e) => Some(e),
+            UnexpectedStatus(..) => None,
+            ClientAssertion(e) => Some(e),
+        }
+    }
+}
+
+pub(crate) fn post_form(url: &str, headers: Vec<(String, String)>, body: Vec<u8>) -> HttpRequest {
+    let mut headers = headers;
+    headers.push(("Accept".into(), "application/json".into()));
+    HttpRequest {
+        method: HttpMethod::Post,
+        url: url.to_owned(),
+        headers,
+        body,
+    }
+}
+
+pub(crate) fn get_json(url: &str) -> HttpRequest {
+    HttpRequest {
+        method: HttpMethod::Get,
+        url: url.to_owned(),
+        headers: vec![("Accept".into(), "application/json".into())],
+        body: vec![],
+    }
+}
+
+fn parse_error_response<T, E>(response: HttpResponse) -> Result<T, RequestError<E>> {
+    match response.status {
+        // RFC 6749 section 5.2: error responses use 400, or 401 when the
+        // client authentication failed.
+        400 | 401 => match serde_json::from_slice(&response.body) {
+            Ok(e) => Err(RequestError::ServerResponse(e)),
+            Err(_) => Err(RequestError::UnexpectedStatus(
+                response.status,
+                response.body,
+            )),
+        },
+        status => Err(RequestError::UnexpectedStatus(status, response.body)),
+    }
+}
+
+pub(crate) fn parse_token_response<T, E>(response: HttpResponse) -> Result<T, RequestError<E>>
+where
+    T: DeserializeOwned,
+{
+    match response.status {
+        200..=299 => serde_json::from_slice(&response.body).map_err(RequestError::Parse),
+        _ => parse_error_response(response),
+    }
+}
+
+// For plain JSON documents (e.g. discovery, JWKS) where a non 2xx status
+// is never an OAuth error response.
+pub(crate) fn parse_json_response<T, E>(response: HttpResponse) -> Result<T, RequestError<E>>
+where
+    T: DeserializeOwned,
+{
+    match response.status {
+        200..=299 => serde_json::from_slice(&response.body).map_err(RequestError::Parse),
+        status => Err(RequestError::UnexpectedStatus(status, response.body)),
+    }
+}
+
+// For endpoints like revocation (RFC 7009 section 2.2) where the content of
+// a successful response is meaningless, and often empty.
+pub(crate) fn parse_empty_response<E>(response: HttpResponse) -> Result<(), RequestError<E>> {
+    match response.status {
+        200..=299 => Ok(()),
+        _ => parse_error_response(response),
+    }
+}
+
+#[cfg(test)]
+mod http_tests {
+    use super::*;
+    use crate::{AuthCodeToken, RefreshTokenRequest, Token};
+
+    const TOKEN: &str = r#"{
+        "access_token": "2YotnFZFEjr1zCsicMWpAA",
+        "token_type": "Bearer",
+        "expires_in": 3600,
+        "refresh_token": "tGzv3JOkF0XG5Qx2TlKWIA"
+    }"#;
+
+    #[test]
+    fn test_execute_success() {
+        let client = MockHttpClient::new();
+        client.push_response(HttpResponse::new(200, TOKEN.into()));
+        let request = RefreshTokenRequest::new("tGzv3JOkF0XG5Qx2TlKWIA".into());
+
+        let token: AuthCodeToken = request
+            .execute(&client, "https://server.example.com/token")
+            .unwrap();
+        assert_eq!(token.access_token(), "2YotnFZFEjr1zCsicMWpAA");
+
+        let sent = client.get_requests();
+        assert_eq!(sent.len(), 1);
+        assert_eq!(sent[0].method, HttpMethod::Post);
+        assert_eq!(sent[0].url, "https://server.example.com/token");
+        assert_eq!(sent[0].body, request.get_request_body());
+        assert!(sent[0].headers.contains(&(
+            "Content-Type".into(),
+            "application/x-www-form-urlencoded".into()
+        )));
+    }
+
+    #[test]
+    fn test_execute_async_success() {
+        let client = MockHttpClient::new();
+        client.push_response(HttpResponse::new(200, TOKEN.into()));
+        let request = RefreshTokenRequest::new("tGzv3JOkF0XG5Qx2TlKWIA".into());
+
+        let token: AuthCodeToken =
+            block_on(request.execute_async(&client, "https://server.example.com/token")).unwrap();
+        assert_eq!(token.refresh_token(), Some("tGzv3JOkF0XG5Qx2TlKWIA".into()));
+    }
+
+    #[test]
+    fn test_execute_server_error() {
+        let client = MockHttpClient::new();
+        client.push_response(HttpResponse::new(
+            400,
+            r#"{"error": "invalid_grant", "error_description": "expired"}"#.into(),
+        ));
+        let request = RefreshTokenRequest::new("tGzv3JOkF0XG5Qx2TlKWIA".into());
+
+        let res: Result<AuthCodeToken, _> =
+            request.execute(&client, "https://server.example.com/token");
+        assert!(matches!(res, Err(RequestError::ServerResponse(_))));
+    }
+
+    #[test]
+    fn test_execute_unexpected_status() {
+        let client = MockHttpClient::new();
+        client.push_response(HttpResponse::new(503, "unavailable".into()));
+        let request = RefreshTokenRequest::new("tGzv3JOkF0XG5Qx2TlKWIA".into());
+
+        let res: Result<AuthCodeToken, _> =
+            request.execute(&client, "https://server.example.com/token");
+        assert!(matches!(res, Err(RequestError::UnexpectedStatus(503, _))));
+    }
+
+    #[test]
+    fn test_execute_transport_error() {
+        let client = MockHttpClient::new();
+        let request = RefreshTokenRequest::new("tGzv3JOkF0XG5Qx2TlKWIA".into());
+
+        let res: Result<AuthCodeToken, _> =
+            request.execute(&client, "https://server.example.com/token");
+        assert!(matches!(
+            res,
+            Err(RequestError::Transport(MockHttpClientError))
+        ));
+    }
+}
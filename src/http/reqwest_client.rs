@@ -0,0 +1,63 @@
+use super::{AsyncHttpClient, HttpMethod, HttpRequest, HttpResponse};
+use std::future::Future;
+
+fn to_reqwest_method(method: HttpMethod) -> reqwest::Method {
+    match method {
+        HttpMethod::Get => reqwest::Method::GET,
+        HttpMethod::Post => reqwest::Method::POST,
+    }
+}
+
+fn to_headers(headers: &reqwest::header::HeaderMap) -> Vec<(String, String)> {
+    headers
+        .iter()
+        .filter_map(|(k, v)| Some((k.to_string(), v.to_str().ok()?.to_owned())))
+        .collect()
+}
+
+impl AsyncHttpClient for reqwest::Client {
+    type Error = reqwest::Error;
+
+    fn execute(
+        &self,
+        request: HttpRequest,
+    ) -> impl Future<Output = Result<HttpResponse, Self::Error>> + Send {
+        let mut builder = self.request(to_reqwest_method(request.method), &request.url);
+        for (k, v) in request.headers.iter() {
+            builder = builder.header(k, v);
+        }
+        let builder = builder.body(request.body);
+        async move {
+            let response = builder.send().await?;
+            let status = response.status().as_u16();
+            let headers = to_headers(response.headers());
+            let body = response.bytes().await?.to_vec();
+            Ok(HttpResponse {
+                status,
+                headers,
+                body,
+            })
+        }
+    }
+}
+
+#[cfg(feature = "reqwest-blocking")]
+impl super::HttpClient for reqwest::blocking::Client {
+    type Error = reqwest::Error;
+
+    fn execute(&self, request: HttpRequest) -> Result<HttpResponse, Self::Error> {
+        let mut builder = self.request(to_reqwest_method(request.method), &request.url);
+        for (k, v) in request.headers.iter() {
+            builder = builder.header(k, v);
+        }
+        let response = builder.body(request.body).send()?;
+        let status = response.status().as_u16();
+        let headers = to_headers(response.headers());
+        let body = response.bytes()?.to_vec();
+        Ok(HttpResponse {
+            status,
+            headers,
+            body,
+        })
+    }
+}
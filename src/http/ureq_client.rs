@@ -0,0 +1,38 @@
+use super::{HttpClient, HttpMethod, HttpRequest, HttpResponse};
+
+impl HttpClient for ureq::Agent {
+    type Error = ureq::Error;
+
+    fn execute(&self, request: HttpRequest) -> Result<HttpResponse, Self::Error> {
+        let method = match request.method {
+            HttpMethod::Get => ureq::http::Method::GET,
+            HttpMethod::Post => ureq::http::Method::POST,
+        };
+        let mut builder = ureq::http::Request::builder()
+            .method(method)
+            .uri(&request.url);
+        for (k, v) in request.headers.iter() {
+            builder = builder.header(k, v);
+        }
+        let http_request = builder.body(request.body)?;
+        // OAuth error responses come with 4xx status codes and have to be
+        // parsed, so they must not be turned into transport errors.
+        let http_request = self
+            .configure_request(http_request)
+            .http_status_as_error(false)
+            .build();
+        let mut response = self.run(http_request)?;
+        let status = response.status().as_u16();
+        let headers = response
+            .headers()
+            .iter()
+            .filter_map(|(k, v)| Some((k.to_string(), v.to_str().ok()?.to_owned())))
+            .collect();
+        let body = response.body_mut().read_to_vec()?;
+        Ok(HttpResponse {
+            status,
+            headers,
+            body,
+        })
+    }
+}
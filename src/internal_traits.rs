@@ -1,3 +1,5 @@
+use crate::ClientAuth;
+
 pub trait OAuthParams {
     fn get_grant_type(&self) -> Option<String> {
         None
@@ -31,6 +33,42 @@ pub trait OAuthParams {
         None
     }
 
+    fn get_nonce(&self) -> Option<String> {
+        None
+    }
+
+    fn get_prompt(&self) -> Option<String> {
+        None
+    }
+
+    fn get_max_age(&self) -> Option<String> {
+        None
+    }
+
+    fn get_ui_locales(&self) -> Option<String> {
+        None
+    }
+
+    fn get_login_hint(&self) -> Option<String> {
+        None
+    }
+
+    fn get_id_token_hint(&self) -> Option<String> {
+        None
+    }
+
+    fn get_acr_values(&self) -> Option<String> {
+        None
+    }
+
+    fn get_display(&self) -> Option<String> {
+        None
+    }
+
+    fn get_claims(&self) -> Option<String> {
+        None
+    }
+
     fn get_extra_params_mut(&mut self) -> Option<&mut Vec<(String, String)>> {
         None
     }
@@ -54,4 +92,64 @@ pub trait OAuthParams {
     fn get_refresh_token(&self) -> Option<String> {
         None
     }
+
+    fn get_code_challenge(&self) -> Option<String> {
+        None
+    }
+
+    fn get_code_challenge_method(&self) -> Option<String> {
+        None
+    }
+
+    fn get_code_verifier(&self) -> Option<String> {
+        None
+    }
+
+    fn get_client_auth(&self) -> Option<&ClientAuth> {
+        None
+    }
+
+    fn get_device_code(&self) -> Option<String> {
+        None
+    }
+
+    fn get_subject_token(&self) -> Option<String> {
+        None
+    }
+
+    fn get_subject_token_type(&self) -> Option<String> {
+        None
+    }
+
+    fn get_actor_token(&self) -> Option<String> {
+        None
+    }
+
+    fn get_actor_token_type(&self) -> Option<String> {
+        None
+    }
+
+    fn get_requested_token_type(&self) -> Option<String> {
+        None
+    }
+
+    fn get_resources_ref(&self) -> Option<&Vec<String>> {
+        None
+    }
+
+    fn get_audiences_ref(&self) -> Option<&Vec<String>> {
+        None
+    }
+
+    fn get_assertion(&self) -> Option<String> {
+        None
+    }
+
+    fn get_token(&self) -> Option<String> {
+        None
+    }
+
+    fn get_token_type_hint(&self) -> Option<String> {
+        None
+    }
 }
@@ -0,0 +1,299 @@
+use crate::audience::Audience;
+use crate::http::{AsyncHttpClient, HttpClient, RequestError};
+use crate::scope::Scope;
+use crate::utils;
+use crate::{ClientAuth, OAuthParams, Token, TokenTypeHint};
+use serde::{self, Deserialize};
+use serde_json::Value;
+use std::collections::HashMap;
+
+// Token Introspection Request (RFC 7662 section 2.1).
+#[derive(Debug)]
+pub struct IntrospectionRequest {
+    token: String,
+    token_type_hint: Option<TokenTypeHint>,
+    extras: Option<Vec<(String, String)>>,
+    client_auth: Option<ClientAuth>,
+}
+
+impl OAuthParams for IntrospectionRequest {
+    fn get_extra_params_mut(&mut self) -> Option<&mut Vec<(String, String)>> {
+        if self.extras.is_none() {
+            self.extras = Some(vec![])
+        }
+        self.extras.as_mut()
+    }
+
+    fn get_extra_params_ref(&self) -> Option<&Vec<(String, String)>> {
+        self.extras.as_ref()
+    }
+
+    fn get_client_auth(&self) -> Option<&ClientAuth> {
+        self.client_auth.as_ref()
+    }
+
+    fn get_token(&self) -> Option<String> {
+        Some(self.token.clone())
+    }
+
+    fn get_token_type_hint(&self) -> Option<String> {
+        self.token_type_hint.as_ref().map(|h| h.as_str().to_owned())
+    }
+}
+
+impl IntrospectionRequest {
+    pub fn new(token: String) -> Self {
+        Self {
+            token,
+            token_type_hint: None,
+            extras: None,
+            client_auth: None,
+        }
+    }
+
+    pub fn from_access_token<T>(token: &T) -> Self
+    where
+        T: Token,
+    {
+        let mut request = Self::new(token.access_token());
+        request.set_token_type_hint(TokenTypeHint::AccessToken);
+        request
+    }
+
+    pub fn set_token_type_hint(&mut self, token_type_hint: TokenTypeHint) {
+        self.token_type_hint = Some(token_type_hint);
+    }
+
+    pub fn set_client_auth(&mut self, client_auth: ClientAuth) {
+        self.client_auth = Some(client_auth);
+    }
+
+    pub fn add_extra_param(&mut self, key: String, value: String) {
+        utils::add_extra_param(self, key, value);
+    }
+
+    pub fn get_request_params_as_vec(&self) -> Vec<(String, String)> {
+        utils::get_request_params_as_vec(self)
+    }
+
+    pub fn get_headers(&self) -> Vec<(String, String)> {
+        utils::get_headers(self)
+    }
+
+    pub fn get_request_body(&self) -> Vec<u8> {
+        utils::get_request_body(self)
+    }
+
+    pub fn execute<C>(
+        &self,
+        client: &C,
+        introspection_url: &str,
+    ) -> Result<IntrospectionResponse, RequestError<C::Error>>
+    where
+        C: HttpClient,
+    {
+        utils::execute_request(self, client, introspection_url)
+    }
+
+    pub async fn execute_async<C>(
+        &self,
+        client: &C,
+        introspection_url: &str,
+    ) -> Result<IntrospectionResponse, RequestError<C::Error>>
+    where
+        C: AsyncHttpClient,
+    {
+        utils::execute_request_async(self, client, introspection_url).await
+    }
+}
+
+// Introspection Response (RFC 7662 section 2.2). Only `active` is required;
+// an inactive token usually comes back with nothing else. Extension claims
+// are kept in `extras`.
+#[derive(Debug, Deserialize, PartialEq, Eq)]
+pub struct IntrospectionResponse {
+    active: bool,
+    scope: Option<Scope>,
+    client_id: Option<String>,
+    username: Option<String>,
+    token_type: Option<String>,
+    exp: Option<u64>,
+    iat: Option<u64>,
+    nbf: Option<u64>,
+    sub: Option<String>,
+    aud: Option<Audience>,
+    iss: Option<String>,
+    jti: Option<String>,
+    #[serde(flatten)]
+    extras: HashMap<String, Value>,
+}
+
+impl IntrospectionResponse {
+    pub fn active(&self) -> bool {
+        self.active
+    }
+
+    pub fn scopes(&self) -> Option<String> {
+        self.scope.as_ref().map(Scope::to_space_separated)
+    }
+
+    pub fn client_id(&self) -> Option<String> {
+        self.client_id.clone()
+    }
+
+    pub fn username(&self) -> Option<String> {
+        self.username.clone()
+    }
+
+    pub fn token_type(&self) -> Option<String> {
+        self.token_type.clone()
+    }
+
+    pub fn exp(&self) -> Option<u64> {
+        self.exp
+    }
+
+    pub fn iat(&self) -> Option<u64> {
+        self.iat
+    }
+
+    pub fn nbf(&self) -> Option<u64> {
+        self.nbf
+    }
+
+    pub fn sub(&self) -> Option<String> {
+        self.sub.clone()
+    }
+
+    pub fn aud(&self) -> Option<Vec<String>> {
+        self.aud.as_ref().map(Audience::to_vec)
+    }
+
+    pub fn has_audience(&self, audience: &str) -> bool {
+        self.aud.as_ref().is_some_and(|aud| aud.contains(audience))
+    }
+
+    pub fn iss(&self) -> Option<String> {
+        self.iss.clone()
+    }
+
+    pub fn jti(&self) -> Option<String> {
+        self.jti.clone()
+    }
+
+    pub fn get_extra_field(&self, name: &str) -> Option<Value> {
+        self.extras.get(name).cloned()
+    }
+
+    pub fn get_extra_fields(&self) -> HashMap<String, Value> {
+        self.extras.clone()
+    }
+}
+
+#[cfg(test)]
+mod introspection_request_tests {
+    use super::*;
+    use crate::{HttpResponse, MockHttpClient, TokenResponse};
+    use serde_json::json;
+
+    #[test]
+    fn test_request_params() {
+        let token = TokenResponse::new("mF_9.B5f-4.1JqM".into(), "Bearer".into());
+        let mut request = IntrospectionRequest::from_access_token(&token);
+        request.set_client_auth(ClientAuth::ClientSecretPost {
+            client_id: "rs08".into(),
+            client_secret: "secret".into(),
+        });
+        assert_eq!(
+            String::from_utf8(request.get_request_body()).unwrap(),
+            "client_id=rs08&client_secret=secret&token=mF_9.B5f-4.1JqM&token_type_hint=access_token"
+        );
+    }
+
+    #[test]
+    fn test_response_deserialization() {
+        // RFC 7662 section 2.2 example, with `aud` and an extension claim.
+        let json_data = r#"{
+            "active": true,
+            "client_id": "l238j323ds-23ij4",
+            "username": "jdoe",
+            "scope": "read write dolphin",
+            "sub": "Z5O3upPC88QrAjx00dis",
+            "aud": "https://protected.example.net/resource",
+            "iss": "https://server.example.com/",
+            "exp": 1419356238,
+            "iat": 1419350238,
+            "extension_field": "twenty-seven"
+        }"#;
+        let response: IntrospectionResponse = serde_json::from_str(json_data).unwrap();
+        assert!(response.active());
+        assert_eq!(response.client_id(), Some("l238j323ds-23ij4".to_owned()));
+        assert_eq!(response.username(), Some("jdoe".to_owned()));
+        assert_eq!(response.scopes(), Some("read write dolphin".to_owned()));
+        assert_eq!(response.sub(), Some("Z5O3upPC88QrAjx00dis".to_owned()));
+        assert_eq!(
+            response.aud(),
+            Some(vec!["https://protected.example.net/resource".to_owned()])
+        );
+        assert!(response.has_audience("https://protected.example.net/resource"));
+        assert_eq!(
+            response.iss(),
+            Some("https://server.example.com/".to_owned())
+        );
+        assert_eq!(response.exp(), Some(1419356238));
+        assert_eq!(response.iat(), Some(1419350238));
+        assert_eq!(response.nbf(), None);
+        assert_eq!(response.token_type(), None);
+        assert_eq!(response.jti(), None);
+        assert_eq!(
+            response.get_extra_field("extension_field"),
+            Some(json!("twenty-seven"))
+        );
+        assert_eq!(response.get_extra_fields().len(), 1);
+    }
+
+    #[test]
+    fn test_response_array_scope_and_audience() {
+        let json_data = r#"{
+            "active": true,
+            "scope": ["read", "write"],
+            "aud": ["api1", "api2"]
+        }"#;
+        let response: IntrospectionResponse = serde_json::from_str(json_data).unwrap();
+        assert_eq!(response.scopes(), Some("read write".to_owned()));
+        assert_eq!(
+            response.aud(),
+            Some(vec!["api1".to_owned(), "api2".to_owned()])
+        );
+        assert!(response.has_audience("api2"));
+        assert!(!response.has_audience("api3"));
+    }
+
+    #[test]
+    fn test_execute_inactive() {
+        let client = MockHttpClient::new();
+        client.push_response(HttpResponse::new(200, r#"{"active": false}"#.into()));
+        let request = IntrospectionRequest::new("2YotnFZFEjr1zCsicMWpAA".into());
+        let response = request
+            .execute(&client, "https://server.example.com/introspect")
+            .unwrap();
+        assert!(!response.active());
+        assert_eq!(response.scopes(), None);
+        assert!(response.get_extra_fields().is_empty());
+
+        let requests = client.get_requests();
+        assert_eq!(requests[0].url, "https://server.example.com/introspect");
+        assert_eq!(requests[0].body, b"token=2YotnFZFEjr1zCsicMWpAA".to_vec());
+    }
+
+    #[test]
+    fn test_execute_missing_active() {
+        let client = MockHttpClient::new();
+        client.push_response(HttpResponse::new(200, r#"{"scope": "read"}"#.into()));
+        let request = IntrospectionRequest::new("2YotnFZFEjr1zCsicMWpAA".into());
+        assert!(matches!(
+            request.execute(&client, "https://server.example.com/introspect"),
+            Err(RequestError::Parse(_))
+        ));
+    }
+}
@@ -0,0 +1,384 @@
+use super::{
+    base64url_decode, base64url_encode, JoseError, JwsAlgorithm, VerifyingKey, MIN_RSA_KEY_BITS,
+};
+use rsa::traits::PublicKeyParts;
+use rsa::{BigUint, RsaPublicKey};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+
+// Public JSON Web Key (RFC 7517 section 4) with the members of the RSA and
+// EC key types (RFC 7518 section 6) and OKP (RFC 8037). Private members, if
+// any, are dropped.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Jwk {
+    kty: String,
+    #[serde(rename = "use", skip_serializing_if = "Option::is_none")]
+    key_use: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    alg: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    kid: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    crv: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    n: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    e: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    x: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    y: Option<String>,
+}
+
+fn required_member(value: &Option<String>, name: &str) -> Result<Vec<u8>, JoseError> {
+    match value {
+        Some(v) => base64url_decode(v),
+        None => Err(JoseError::InvalidKey(format!("missing JWK member {name}"))),
+    }
+}
+
+// EC coordinates are the full size of the field (RFC 7518 section 6.2.1.2).
+fn coordinate(value: &Option<String>, name: &str, len: usize) -> Result<Vec<u8>, JoseError> {
+    let bytes = required_member(value, name)?;
+    if bytes.len() != len {
+        return Err(JoseError::InvalidKey(format!(
+            "invalid length for JWK member {name}"
+        )));
+    }
+    Ok(bytes)
+}
+
+fn invalid_key<E: std::fmt::Display>(e: E) -> JoseError {
+    JoseError::InvalidKey(e.to_string())
+}
+
+impl Jwk {
+    pub fn key_type(&self) -> String {
+        self.kty.clone()
+    }
+
+    pub fn key_use(&self) -> Option<String> {
+        self.key_use.clone()
+    }
+
+    pub fn algorithm(&self) -> Option<String> {
+        self.alg.clone()
+    }
+
+    pub fn key_id(&self) -> Option<String> {
+        self.kid.clone()
+    }
+
+    pub fn curve(&self) -> Option<String> {
+        self.crv.clone()
+    }
+
+    // Whether the key type and curve can be used with the algorithm, and the
+    // `alg` member, when present, names it.
+    pub fn is_compatible(&self, alg: JwsAlgorithm) -> bool {
+        use JwsAlgorithm::*;
+        if self.alg.as_deref().is_some_and(|a| a != alg.as_str()) {
+            return false;
+        }
+        matches!(
+            (self.kty.as_str(), self.crv.as_deref(), alg),
+            ("RSA", _, RS256 | RS384 | RS512 | PS256 | PS384 | PS512)
+                | ("EC", Some("P-256"), ES256)
+                | ("EC", Some("P-384"), ES384)
+                | ("EC", Some("P-521"), ES512)
+                | ("OKP", Some("Ed25519"), EdDSA)
+        )
+    }
+
+    // JWK Thumbprint (RFC 7638) using SHA-256, base64url encoded.
+    pub fn thumbprint(&self) -> Result<String, JoseError> {
+        let required = |value: &Option<String>, name: &str| {
+            value
+                .clone()
+                .ok_or_else(|| JoseError::InvalidKey(format!("missing JWK member {name}")))
+        };
+        // only the required members, ordered lexicographically and without
+        // whitespace (RFC 7638 section 3.2).
+        let mut members = BTreeMap::new();
+        members.insert("kty", self.kty.clone());
+        match self.kty.as_str() {
+            "RSA" => {
+                members.insert("e", required(&self.e, "e")?);
+                members.insert("n", required(&self.n, "n")?);
+            }
+            "EC" => {
+                members.insert("crv", required(&self.crv, "crv")?);
+                members.insert("x", required(&self.x, "x")?);
+                members.insert("y", required(&self.y, "y")?);
+            }
+            "OKP" => {
+                members.insert("crv", required(&self.crv, "crv")?);
+                members.insert("x", required(&self.x, "x")?);
+            }
+            kty => {
+                return Err(JoseError::InvalidKey(format!("unsupported JWK kty {kty}")));
+            }
+        }
+        let json =
+            serde_json::to_vec(&members).map_err(|e| JoseError::Serialization(e.to_string()))?;
+        Ok(base64url_encode(&Sha256::digest(json)))
+    }
+
+    pub fn to_verifying_key(&self) -> Result<VerifyingKey, JoseError> {
+        match (self.kty.as_str(), self.crv.as_deref()) {
+            ("RSA", _) => {
+                let n = BigUint::from_bytes_be(&required_member(&self.n, "n")?);
+                let e = BigUint::from_bytes_be(&required_member(&self.e, "e")?);
+                let key = RsaPublicKey::new(n, e).map_err(invalid_key)?;
+                if key.size() * 8 < MIN_RSA_KEY_BITS {
+                    return Err(JoseError::InvalidKey("RSA key is too small".into()));
+                }
+                Ok(VerifyingKey::Rsa(Box::new(key)))
+            }
+            ("EC", Some("P-256")) => {
+                let x = coordinate(&self.x, "x", 32)?;
+                let y = coordinate(&self.y, "y", 32)?;
+                let point = p256::EncodedPoint::from_affine_coordinates(
+                    x.as_slice().into(),
+                    y.as_slice().into(),
+                    false,
+                );
+                p256::ecdsa::VerifyingKey::from_encoded_point(&point)
+                    .map(VerifyingKey::P256)
+                    .map_err(invalid_key)
+            }
+            ("EC", Some("P-384")) => {
+                let x = coordinate(&self.x, "x", 48)?;
+                let y = coordinate(&self.y, "y", 48)?;
+                let point = p384::EncodedPoint::from_affine_coordinates(
+                    x.as_slice().into(),
+                    y.as_slice().into(),
+                    false,
+                );
+                p384::ecdsa::VerifyingKey::from_encoded_point(&point)
+                    .map(VerifyingKey::P384)
+                    .map_err(invalid_key)
+            }
+            ("EC", Some("P-521")) => {
+                let x = coordinate(&self.x, "x", 66)?;
+                let y = coordinate(&self.y, "y", 66)?;
+                let point = p521::EncodedPoint::from_affine_coordinates(
+                    x.as_slice().into(),
+                    y.as_slice().into(),
+                    false,
+                );
+                p521::ecdsa::VerifyingKey::from_encoded_point(&point)
+                    .map(VerifyingKey::P521)
+                    .map_err(invalid_key)
+            }
+            ("OKP", Some("Ed25519")) => {
+                let x: [u8; 32] = coordinate(&self.x, "x", 32)?
+                    .try_into()
+                    .expect("length is checked");
+                ed25519_dalek::VerifyingKey::from_bytes(&x)
+                    .map(VerifyingKey::Ed25519)
+                    .map_err(invalid_key)
+            }
+            (kty, crv) => Err(JoseError::InvalidKey(format!(
+                "unsupported JWK kty {kty} crv {}",
+                crv.unwrap_or_default()
+            ))),
+        }
+    }
+}
+
+// JWK Set (RFC 7517 section 5), as published at a `jwks_uri`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JwkSet {
+    keys: Vec<Jwk>,
+}
+
+impl JwkSet {
+    pub fn new(keys: Vec<Jwk>) -> Self {
+        Self { keys }
+    }
+
+    pub fn from_json(json: &[u8]) -> Result<Self, serde_json::Error> {
+        serde_json::from_slice(json)
+    }
+
+    pub fn keys(&self) -> &[Jwk] {
+        &self.keys
+    }
+
+    pub fn find_by_key_id(&self, kid: &str) -> Option<&Jwk> {
+        self.keys.iter().find(|k| k.kid.as_deref() == Some(kid))
+    }
+
+    // Keys which can verify a JWS using `alg`. When given, `kid` must match,
+    // and keys with a `use` member other than `key_use` are left out.
+    pub fn select_keys(
+        &self,
+        kid: Option<&str>,
+        alg: JwsAlgorithm,
+        key_use: Option<&str>,
+    ) -> Vec<&Jwk> {
+        self.keys
+            .iter()
+            .filter(|k| kid.is_none() || k.kid.as_deref() == kid)
+            .filter(|k| match (k.key_use.as_deref(), key_use) {
+                (Some(a), Some(b)) => a == b,
+                _ => true,
+            })
+            .filter(|k| k.is_compatible(alg))
+            .collect()
+    }
+
+    // Signing key for a JWS with the given headers. Without a `kid` the set
+    // must hold a single candidate (OpenID Connect Core 1.0 section 10.1).
+    pub fn find_signing_key(&self, kid: Option<&str>, alg: JwsAlgorithm) -> Option<&Jwk> {
+        match (kid, self.select_keys(kid, alg, Some("sig")).as_slice()) {
+            (Some(_), [jwk, ..]) | (None, [jwk]) => Some(jwk),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod jwk_tests {
+    use super::*;
+    use crate::jose::{test_keys, JwsAlgorithm, SigningKey};
+
+    // RFC 7517 appendix A.1
+    const JWKS: &str = r#"{"keys": [
+        {"kty": "EC",
+         "crv": "P-256",
+         "x": "MKBCTNIcKUSDii11ySs3526iDZ8AiTo7Tu6KPAqv7D4",
+         "y": "4Etl6SRW2YiLUrN5vfvVHuhp7x8PxltmWWlbbM4IFyM",
+         "use": "enc",
+         "kid": "1"},
+        {"kty": "RSA",
+         "n": "0vx7agoebGcQSuuPiLJXZptN9nndrQmbXEps2aiAFbWhM78LhWx4cbbfAAtVT86zwu1RK7aPFFxuhDR1L6tSoc_BJECPebWKRXjBZCiFV4n3oknjhMstn64tZ_2W-5JsGY4Hc5n9yBXArwl93lqt7_RN5w6Cf0h4QyQ5v-65YGjQR0_FDW2QvzqY368QQMicAtaSqzs8KJZgnYb9c7d0zgdAZHzu6qMQvRL5hajrn1n91CbOpbISD08qNLyrdkt-bFTWhAI4vMQFh6WeZu0fM4lFd2NcRwr3XPksINHaQ-G_xBniIqbw0Ls1jF44-csFCur-kEgU8awapJzKnqDKgw",
+         "e": "AQAB",
+         "alg": "RS256",
+         "kid": "2011-04-29"}
+    ]}"#;
+
+    #[test]
+    fn test_jwk_set() {
+        let jwks = JwkSet::from_json(JWKS.as_bytes()).unwrap();
+        assert_eq!(jwks.keys().len(), 2);
+
+        let ec = jwks.find_by_key_id("1").unwrap();
+        assert_eq!(ec.key_type(), "EC");
+        assert_eq!(ec.curve(), Some("P-256".to_owned()));
+        assert_eq!(ec.key_use(), Some("enc".to_owned()));
+        assert!(matches!(ec.to_verifying_key(), Ok(VerifyingKey::P256(_))));
+
+        let rsa = jwks.find_by_key_id("2011-04-29").unwrap();
+        assert_eq!(rsa.algorithm(), Some("RS256".to_owned()));
+        let key = rsa.to_verifying_key().unwrap();
+        assert!(key.is_compatible(JwsAlgorithm::RS256));
+
+        assert_eq!(jwks.find_by_key_id("unknown"), None);
+    }
+
+    #[test]
+    fn test_select_keys() {
+        let jwks = JwkSet::from_json(JWKS.as_bytes()).unwrap();
+        fn kids(keys: Vec<&Jwk>) -> Vec<Option<String>> {
+            keys.into_iter().map(Jwk::key_id).collect()
+        }
+        assert_eq!(
+            kids(jwks.select_keys(None, JwsAlgorithm::RS256, Some("sig"))),
+            vec![Some("2011-04-29".to_owned())]
+        );
+        // the key is restricted to RS256 by its alg member.
+        assert!(jwks
+            .select_keys(None, JwsAlgorithm::RS512, Some("sig"))
+            .is_empty());
+        assert!(jwks
+            .select_keys(None, JwsAlgorithm::ES256, Some("sig"))
+            .is_empty());
+        assert_eq!(
+            kids(jwks.select_keys(Some("1"), JwsAlgorithm::ES256, None)),
+            vec![Some("1".to_owned())]
+        );
+        assert!(jwks
+            .select_keys(Some("1"), JwsAlgorithm::RS256, None)
+            .is_empty());
+    }
+
+    #[test]
+    fn test_thumbprint() {
+        // RFC 7638 section 3.1
+        let jwks = JwkSet::from_json(JWKS.as_bytes()).unwrap();
+        assert_eq!(
+            jwks.find_by_key_id("2011-04-29").unwrap().thumbprint(),
+            Ok("NzbLsXh8uDCcd-6MNwXF4W_7noWXFZAfHkxZsRGC9Xs".to_owned())
+        );
+        // members other than the required ones do not change it.
+        let ec = jwks.find_by_key_id("1").unwrap();
+        let mut stripped = ec.clone();
+        stripped.kid = None;
+        stripped.key_use = None;
+        assert_eq!(ec.thumbprint(), stripped.thumbprint());
+
+        let oct: Jwk = serde_json::from_str(r#"{"kty": "oct"}"#).unwrap();
+        assert!(oct.thumbprint().is_err());
+    }
+
+    #[test]
+    fn test_to_verifying_key_p521() {
+        // RFC 7520 section 3.1, the private member is ignored.
+        let jwk: Jwk = serde_json::from_str(
+            r#"{
+                "kty": "EC",
+                "kid": "bilbo.baggins@hobbiton.example",
+                "use": "sig",
+                "crv": "P-521",
+                "x": "AHKZLLOsCOzz5cY97ewNUajB957y-C-U88c3v13nmGZx6sYl_oJXu9A5RkTKqjqvjyekWF-7ytDyRXYgCF5cj0Kt",
+                "y": "AdymlHvOiLxXkEhayXQnNCvDX4h9htZaCJN34kfmC6pV5OhQHiraVySsUdaQkAgDPrwQrJmbnX9cwlGfP-HqHZR1",
+                "d": "AAhRON2r9cqXX1hg-RoI6R1tX5p2rUAYdmpHZoC1XNM56KtscrX6zbKipQrCW9CGZH3T4ubpnoTKLDYJ_fF3_rJt"
+            }"#,
+        )
+        .unwrap();
+        assert!(jwk.is_compatible(JwsAlgorithm::ES512));
+        assert!(!jwk.is_compatible(JwsAlgorithm::ES256));
+        let key = jwk.to_verifying_key().unwrap();
+        assert!(key.is_compatible(JwsAlgorithm::ES512));
+    }
+
+    #[test]
+    fn test_to_verifying_key_ed25519() {
+        let signing_key = SigningKey::from_pem(test_keys::ED25519_PKCS8_PEM).unwrap();
+        let VerifyingKey::Ed25519(public) = VerifyingKey::from(&signing_key) else {
+            unreachable!()
+        };
+        let jwk: Jwk = serde_json::from_value(serde_json::json!({
+            "kty": "OKP",
+            "crv": "Ed25519",
+            "x": crate::jose::base64url_encode(public.as_bytes()),
+        }))
+        .unwrap();
+        let key = jwk.to_verifying_key().unwrap();
+        let signature = signing_key.sign(JwsAlgorithm::EdDSA, b"payload").unwrap();
+        assert_eq!(
+            key.verify(JwsAlgorithm::EdDSA, b"payload", &signature),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn test_to_verifying_key_invalid() {
+        let cases = [
+            r#"{"kty": "RSA", "e": "AQAB"}"#,
+            r#"{"kty": "EC", "crv": "P-256", "x": "AAAA", "y": "AAAA"}"#,
+            r#"{"kty": "EC", "crv": "secp256k1", "x": "AAAA", "y": "AAAA"}"#,
+            r#"{"kty": "oct", "k": "AAAA"}"#,
+        ];
+        for jwk in cases {
+            let jwk: Jwk = serde_json::from_str(jwk).unwrap();
+            assert!(
+                matches!(jwk.to_verifying_key(), Err(JoseError::InvalidKey(_))),
+                "{jwk:?}"
+            );
+        }
+    }
+}
@@ -0,0 +1,274 @@
+use super::{
+    base64url_decode, base64url_encode, JoseError, JwsAlgorithm, SigningKey, VerifyingKey,
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JwsHeader {
+    pub alg: JwsAlgorithm,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub typ: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub kid: Option<String>,
+}
+
+impl JwsHeader {
+    pub fn new(alg: JwsAlgorithm) -> Self {
+        Self {
+            alg,
+            typ: None,
+            kid: None,
+        }
+    }
+}
+
+// JWS Compact Serialization (RFC 7515 section 7.1).
+pub fn encode_compact(
+    header: &JwsHeader,
+    payload: &[u8],
+    key: &SigningKey,
+) -> Result<String, JoseError> {
+    let encoded_header =
+        serde_json::to_vec(header).map_err(|e| JoseError::Serialization(e.to_string()))?;
+    let signing_input = format!(
+        "{}.{}",
+        base64url_encode(&encoded_header),
+        base64url_encode(payload)
+    );
+    let signature = key.sign(header.alg, signing_input.as_bytes())?;
+    Ok(format!(
+        "{}.{}",
+        signing_input,
+        base64url_encode(&signature)
+    ))
+}
+
+// Parsed JWS Compact Serialization whose signature is not checked yet.
+pub(crate) struct CompactJws<'a> {
+    pub header: JwsHeader,
+    signing_input: &'a str,
+    payload: Vec<u8>,
+    signature: Vec<u8>,
+}
+
+impl<'a> CompactJws<'a> {
+    // The `alg` header must be one of `allowed_algs`. An unlisted algorithm,
+    // including "none", is rejected before anything else is looked at.
+    pub fn decode(token: &'a str, allowed_algs: &[JwsAlgorithm]) -> Result<Self, JoseError> {
+        let malformed = || JoseError::Serialization("malformed JWS compact serialization".into());
+        let (signing_input, signature) = token.rsplit_once('.').ok_or_else(malformed)?;
+        let (header, payload) = signing_input.split_once('.').ok_or_else(malformed)?;
+        let header: serde_json::Value = serde_json::from_slice(&base64url_decode(header)?)
+            .map_err(|e| JoseError::Serialization(e.to_string()))?;
+        let alg = header
+            .get("alg")
+            .and_then(serde_json::Value::as_str)
+            .ok_or_else(|| JoseError::Serialization("missing alg header".into()))?;
+        match serde_json::from_value::<JwsAlgorithm>(alg.into()) {
+            Ok(alg) if allowed_algs.contains(&alg) => (),
+            _ => return Err(JoseError::AlgorithmNotAllowed(alg.to_owned())),
+        }
+        if header.get("crit").is_some() {
+            return Err(JoseError::UnsupportedCriticalHeader);
+        }
+        let header: JwsHeader =
+            serde_json::from_value(header).map_err(|e| JoseError::Serialization(e.to_string()))?;
+        Ok(Self {
+            header,
+            signing_input,
+            payload: base64url_decode(payload)?,
+            signature: base64url_decode(signature)?,
+        })
+    }
+
+    pub fn verify(self, key: &VerifyingKey) -> Result<Vec<u8>, JoseError> {
+        key.verify(
+            self.header.alg,
+            self.signing_input.as_bytes(),
+            &self.signature,
+        )?;
+        Ok(self.payload)
+    }
+}
+
+#[cfg(test)]
+mod jws_tests {
+    use super::*;
+    use crate::jose::test_keys;
+
+    fn verify_compact(
+        token: &str,
+        key: &VerifyingKey,
+        allowed_algs: &[JwsAlgorithm],
+    ) -> Result<(JwsHeader, Vec<u8>), JoseError> {
+        let jws = CompactJws::decode(token, allowed_algs)?;
+        let header = jws.header.clone();
+        Ok((header, jws.verify(key)?))
+    }
+
+    #[test]
+    fn test_encode_and_verify() {
+        let key = SigningKey::from_pem(test_keys::P256_PKCS8_PEM).unwrap();
+        let mut header = JwsHeader::new(JwsAlgorithm::ES256);
+        header.kid = Some("k1".into());
+        let token = encode_compact(&header, br#"{"sub":"alice"}"#, &key).unwrap();
+
+        let jws = CompactJws::decode(&token, &[JwsAlgorithm::ES256]).unwrap();
+        assert_eq!(jws.header, header);
+        let payload = jws.verify(&VerifyingKey::from(&key)).unwrap();
+        assert_eq!(payload, br#"{"sub":"alice"}"#.to_vec());
+    }
+
+    #[test]
+    fn test_decode_rejects_unlisted_algorithms() {
+        let key = SigningKey::from_secret(test_keys::HMAC_SECRET);
+        let token = encode_compact(&JwsHeader::new(JwsAlgorithm::HS256), b"{}", &key).unwrap();
+        assert_eq!(
+            CompactJws::decode(&token, &[JwsAlgorithm::RS256]).err(),
+            Some(JoseError::AlgorithmNotAllowed("HS256".into()))
+        );
+
+        // {"alg":"none"} with an empty signature.
+        let token = format!(
+            "{}.{}.",
+            base64url_encode(br#"{"alg":"none"}"#),
+            base64url_encode(b"{}")
+        );
+        assert_eq!(
+            CompactJws::decode(&token, &[JwsAlgorithm::RS256]).err(),
+            Some(JoseError::AlgorithmNotAllowed("none".into()))
+        );
+    }
+
+    #[test]
+    fn test_verify_rfc7515_hs256() {
+        // RFC 7515 appendix A.1
+        let token = "eyJ0eXAiOiJKV1QiLA0KICJhbGciOiJIUzI1NiJ9\
+            .eyJpc3MiOiJqb2UiLA0KICJleHAiOjEzMDA4MTkzODAsDQogImh0dHA6Ly9leGFtcGxlLmNvbS9pc19yb290Ijp0cnVlfQ\
+            .dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXk";
+        let secret = base64url_decode(
+            "AyM1SysPpbyDfgZld3umj1qzKObwVMkoqQ-EstJQLr_T-1qS0gZH75aKtMN3Yj0iPS4hcgUuTwjAzZr1Z9CAow",
+        )
+        .unwrap();
+        let (header, payload) = verify_compact(
+            token,
+            &VerifyingKey::from_secret(&secret),
+            &[JwsAlgorithm::HS256],
+        )
+        .unwrap();
+        assert_eq!(header.alg, JwsAlgorithm::HS256);
+        assert_eq!(header.typ.as_deref(), Some("JWT"));
+        let payload: serde_json::Value = serde_json::from_slice(&payload).unwrap();
+        assert_eq!(payload["iss"], "joe");
+        assert_eq!(payload["exp"], 1300819380);
+    }
+
+    #[test]
+    fn test_verify_all_algorithms() {
+        use JwsAlgorithm::*;
+        let cases = [
+            (
+                SigningKey::from_secret(test_keys::HMAC_SECRET),
+                vec![HS256, HS384, HS512],
+            ),
+            (
+                SigningKey::from_pem(test_keys::RSA_PKCS8_PEM).unwrap(),
+                vec![RS256, RS384, RS512, PS256, PS384, PS512],
+            ),
+            (
+                SigningKey::from_pem(test_keys::P256_PKCS8_PEM).unwrap(),
+                vec![ES256],
+            ),
+            (
+                SigningKey::from_pem(test_keys::P384_PKCS8_PEM).unwrap(),
+                vec![ES384],
+            ),
+            (
+                SigningKey::from_pem(test_keys::ED25519_PKCS8_PEM).unwrap(),
+                vec![EdDSA],
+            ),
+        ];
+        for (key, algs) in cases {
+            for alg in algs {
+                let token = encode_compact(&JwsHeader::new(alg), b"payload", &key).unwrap();
+                let (header, payload) =
+                    verify_compact(&token, &VerifyingKey::from(&key), &[alg]).unwrap();
+                assert_eq!(header.alg, alg);
+                assert_eq!(payload, b"payload".to_vec());
+            }
+        }
+    }
+
+    #[test]
+    fn test_verify_key_confusion() {
+        // a token signed with HS256 using the public RSA key as the secret
+        // must not verify with the RSA key, even if HS256 is allowed.
+        let rsa = SigningKey::from_pem(test_keys::RSA_PKCS8_PEM).unwrap();
+        let VerifyingKey::Rsa(public) = VerifyingKey::from(&rsa) else {
+            unreachable!()
+        };
+        let secret = rsa::pkcs1::EncodeRsaPublicKey::to_pkcs1_der(public.as_ref()).unwrap();
+        let token = encode_compact(
+            &JwsHeader::new(JwsAlgorithm::HS256),
+            b"payload",
+            &SigningKey::from_secret(secret.as_bytes()),
+        )
+        .unwrap();
+        let key = VerifyingKey::from(&rsa);
+        assert_eq!(
+            verify_compact(&token, &key, &[JwsAlgorithm::RS256]).err(),
+            Some(JoseError::AlgorithmNotAllowed("HS256".into()))
+        );
+        assert_eq!(
+            verify_compact(&token, &key, &[JwsAlgorithm::RS256, JwsAlgorithm::HS256]).err(),
+            Some(JoseError::AlgorithmMismatch(JwsAlgorithm::HS256))
+        );
+    }
+
+    #[test]
+    fn test_verify_unsigned_token_is_rejected() {
+        let key = VerifyingKey::from_secret(test_keys::HMAC_SECRET);
+        let all = [
+            JwsAlgorithm::HS256,
+            JwsAlgorithm::RS256,
+            JwsAlgorithm::EdDSA,
+        ];
+        for header in [r#"{"alg":"none"}"#, r#"{"alg":"None"}"#, r#"{"typ":"JWT"}"#] {
+            let token = format!(
+                "{}.{}.",
+                base64url_encode(header.as_bytes()),
+                base64url_encode(b"{}")
+            );
+            assert!(verify_compact(&token, &key, &all).is_err(), "{header}");
+        }
+    }
+
+    #[test]
+    fn test_verify_crit_header() {
+        let header = base64url_encode(br#"{"alg":"HS256","crit":["exp"],"exp":1363284000}"#);
+        let signing_input = format!("{}.{}", header, base64url_encode(b"{}"));
+        let signature = SigningKey::from_secret(test_keys::HMAC_SECRET)
+            .sign(JwsAlgorithm::HS256, signing_input.as_bytes())
+            .unwrap();
+        let token = format!("{}.{}", signing_input, base64url_encode(&signature));
+        assert_eq!(
+            verify_compact(
+                &token,
+                &VerifyingKey::from_secret(test_keys::HMAC_SECRET),
+                &[JwsAlgorithm::HS256]
+            )
+            .err(),
+            Some(JoseError::UnsupportedCriticalHeader)
+        );
+    }
+
+    #[test]
+    fn test_decode_malformed() {
+        for token in ["", "abc", "abc.def", "e30.e30.!!"] {
+            assert!(matches!(
+                CompactJws::decode(token, &[JwsAlgorithm::RS256]),
+                Err(JoseError::Serialization(_))
+            ));
+        }
+    }
+}
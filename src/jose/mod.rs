@@ -0,0 +1,116 @@
+mod jwk;
+mod jws;
+mod signing_key;
+#[cfg(test)]
+pub(crate) mod test_keys;
+mod verifying_key;
+
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::fmt::Display;
+
+pub use jwk::{Jwk, JwkSet};
+pub(crate) use jws::{encode_compact, CompactJws, JwsHeader};
+pub use signing_key::SigningKey;
+pub use verifying_key::VerifyingKey;
+
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum JwsAlgorithm {
+    HS256,
+    HS384,
+    HS512,
+    RS256,
+    RS384,
+    RS512,
+    PS256,
+    PS384,
+    PS512,
+    ES256,
+    ES384,
+    ES512,
+    EdDSA,
+}
+
+impl JwsAlgorithm {
+    pub fn as_str(&self) -> &'static str {
+        use JwsAlgorithm::*;
+        match self {
+            HS256 => "HS256",
+            HS384 => "HS384",
+            HS512 => "HS512",
+            RS256 => "RS256",
+            RS384 => "RS384",
+            RS512 => "RS512",
+            PS256 => "PS256",
+            PS384 => "PS384",
+            PS512 => "PS512",
+            ES256 => "ES256",
+            ES384 => "ES384",
+            ES512 => "ES512",
+            EdDSA => "EdDSA",
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum JoseError {
+    InvalidKey(String),
+    // the algorithm cannot be used with the given key.
+    AlgorithmMismatch(JwsAlgorithm),
+    Signing(String),
+    Serialization(String),
+    InvalidSignature,
+    // the `alg` header of a JWS is not one the caller accepts.
+    AlgorithmNotAllowed(String),
+    // the JWS uses extensions marked as critical (RFC 7515 section 4.1.11),
+    // none of which are understood.
+    UnsupportedCriticalHeader,
+}
+
+impl Display for JoseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        use JoseError::*;
+        match self {
+            InvalidKey(e) => write!(f, "JoseError::InvalidKey({e})"),
+            AlgorithmMismatch(alg) => write!(f, "JoseError::AlgorithmMismatch({})", alg.as_str()),
+            Signing(e) => write!(f, "JoseError::Signing({e})"),
+            Serialization(e) => write!(f, "JoseError::Serialization({e})"),
+            InvalidSignature => write!(f, "JoseError::InvalidSignature"),
+            AlgorithmNotAllowed(alg) => write!(f, "JoseError::AlgorithmNotAllowed({alg})"),
+            UnsupportedCriticalHeader => write!(f, "JoseError::UnsupportedCriticalHeader"),
+        }
+    }
+}
+
+impl Error for JoseError {}
+
+// RFC 7518 section 3.3 and 3.5 require a key of 2048 bits or larger.
+pub(crate) const MIN_RSA_KEY_BITS: usize = 2048;
+
+// RFC 7518 section 3.2 requires an HMAC key at least as long as the hash
+// output.
+pub(crate) fn check_hmac_key_len(secret: &[u8], alg: JwsAlgorithm) -> Result<(), JoseError> {
+    let min_len = match alg {
+        JwsAlgorithm::HS384 => 48,
+        JwsAlgorithm::HS512 => 64,
+        _ => 32,
+    };
+    if secret.len() < min_len {
+        return Err(JoseError::InvalidKey(format!(
+            "{} requires an HMAC key of {min_len} bytes or more",
+            alg.as_str()
+        )));
+    }
+    Ok(())
+}
+
+pub(crate) fn base64url_encode(data: &[u8]) -> String {
+    data_encoding::BASE64URL_NOPAD.encode(data)
+}
+
+pub(crate) fn base64url_decode(data: &str) -> Result<Vec<u8>, JoseError> {
+    data_encoding::BASE64URL_NOPAD
+        .decode(data.as_bytes())
+        .map_err(|e| JoseError::Serialization(e.to_string()))
+}
@@ -0,0 +1,256 @@
+use super::signing_key::Key;
+use super::{check_hmac_key_len, JoseError, JwsAlgorithm, SigningKey, MIN_RSA_KEY_BITS};
+use hmac::{Hmac, Mac};
+use rsa::signature::Verifier;
+use rsa::traits::PublicKeyParts;
+use rsa::{Pkcs1v15Sign, Pss, RsaPublicKey};
+use sha2::{Digest, Sha256, Sha384, Sha512};
+
+// Key used to check JWS signatures. For HMAC it is the shared secret.
+#[derive(Clone)]
+pub enum VerifyingKey {
+    Hmac(Vec<u8>),
+    Rsa(Box<RsaPublicKey>),
+    P256(p256::ecdsa::VerifyingKey),
+    P384(p384::ecdsa::VerifyingKey),
+    P521(p521::ecdsa::VerifyingKey),
+    Ed25519(ed25519_dalek::VerifyingKey),
+}
+
+impl std::fmt::Debug for VerifyingKey {
+    // an HMAC secret must not be printed.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let kind = match self {
+            VerifyingKey::Hmac(_) => "Hmac",
+            VerifyingKey::Rsa(_) => "Rsa",
+            VerifyingKey::P256(_) => "P256",
+            VerifyingKey::P384(_) => "P384",
+            VerifyingKey::P521(_) => "P521",
+            VerifyingKey::Ed25519(_) => "Ed25519",
+        };
+        write!(f, "VerifyingKey::{kind}")
+    }
+}
+
+impl From<&SigningKey> for VerifyingKey {
+    fn from(key: &SigningKey) -> Self {
+        match &key.0 {
+            Key::Hmac(secret) => VerifyingKey::Hmac(secret.clone()),
+            Key::Rsa(key) => VerifyingKey::Rsa(Box::new(key.to_public_key())),
+            Key::P256(key) => VerifyingKey::P256(*key.verifying_key()),
+            Key::P384(key) => VerifyingKey::P384(*key.verifying_key()),
+            Key::Ed25519(key) => VerifyingKey::Ed25519(key.verifying_key()),
+        }
+    }
+}
+
+fn invalid_signature<E>(_: E) -> JoseError {
+    JoseError::InvalidSignature
+}
+
+impl VerifyingKey {
+    pub fn from_secret(secret: &[u8]) -> Self {
+        VerifyingKey::Hmac(secret.to_vec())
+    }
+
+    pub fn is_compatible(&self, alg: JwsAlgorithm) -> bool {
+        use JwsAlgorithm::*;
+        matches!(
+            (self, alg),
+            (VerifyingKey::Hmac(_), HS256 | HS384 | HS512)
+                | (
+                    VerifyingKey::Rsa(_),
+                    RS256 | RS384 | RS512 | PS256 | PS384 | PS512
+                )
+                | (VerifyingKey::P256(_), ES256)
+                | (VerifyingKey::P384(_), ES384)
+                | (VerifyingKey::P521(_), ES512)
+                | (VerifyingKey::Ed25519(_), EdDSA)
+        )
+    }
+
+    pub fn verify(
+        &self,
+        alg: JwsAlgorithm,
+        input: &[u8],
+        signature: &[u8],
+    ) -> Result<(), JoseError> {
+        use JwsAlgorithm::*;
+        if !self.is_compatible(alg) {
+            return Err(JoseError::AlgorithmMismatch(alg));
+        }
+        match self {
+            VerifyingKey::Hmac(secret) => {
+                check_hmac_key_len(secret, alg)?;
+                match alg {
+                    HS256 => hmac_verify::<Hmac<Sha256>>(secret, input, signature),
+                    HS384 => hmac_verify::<Hmac<Sha384>>(secret, input, signature),
+                    _ => hmac_verify::<Hmac<Sha512>>(secret, input, signature),
+                }
+            }
+            VerifyingKey::Rsa(key) => {
+                // the variants are public, so the key size is checked here
+                // as well as in Jwk::to_verifying_key.
+                if key.size() * 8 < MIN_RSA_KEY_BITS {
+                    return Err(JoseError::InvalidKey("RSA key is too small".into()));
+                }
+                match alg {
+                    RS256 => key.verify(
+                        Pkcs1v15Sign::new::<Sha256>(),
+                        &Sha256::digest(input),
+                        signature,
+                    ),
+                    RS384 => key.verify(
+                        Pkcs1v15Sign::new::<Sha384>(),
+                        &Sha384::digest(input),
+                        signature,
+                    ),
+                    RS512 => key.verify(
+                        Pkcs1v15Sign::new::<Sha512>(),
+                        &Sha512::digest(input),
+                        signature,
+                    ),
+                    PS256 => key.verify(Pss::new::<Sha256>(), &Sha256::digest(input), signature),
+                    PS384 => key.verify(Pss::new::<Sha384>(), &Sha384::digest(input), signature),
+                    _ => key.verify(Pss::new::<Sha512>(), &Sha512::digest(input), signature),
+                }
+                .map_err(invalid_signature)
+            }
+            VerifyingKey::P256(key) => {
+                // JWS uses the fixed size R || S encoding (RFC 7518 section 3.4).
+                let signature =
+                    p256::ecdsa::Signature::from_slice(signature).map_err(invalid_signature)?;
+                key.verify(input, &signature).map_err(invalid_signature)
+            }
+            VerifyingKey::P384(key) => {
+                let signature =
+                    p384::ecdsa::Signature::from_slice(signature).map_err(invalid_signature)?;
+                key.verify(input, &signature).map_err(invalid_signature)
+            }
+            VerifyingKey::P521(key) => {
+                let signature =
+                    p521::ecdsa::Signature::from_slice(signature).map_err(invalid_signature)?;
+                key.verify(input, &signature).map_err(invalid_signature)
+            }
+            VerifyingKey::Ed25519(key) => {
+                let signature =
+                    ed25519_dalek::Signature::from_slice(signature).map_err(invalid_signature)?;
+                key.verify_strict(input, &signature)
+                    .map_err(invalid_signature)
+            }
+        }
+    }
+}
+
+fn hmac_verify<M: Mac + hmac::digest::KeyInit>(
+    secret: &[u8],
+    input: &[u8],
+    signature: &[u8],
+) -> Result<(), JoseError> {
+    let mut mac = <M as hmac::digest::KeyInit>::new_from_slice(secret).expect("any key length");
+    mac.update(input);
+    // compares in constant time.
+    mac.verify_slice(signature).map_err(invalid_signature)
+}
+
+#[cfg(test)]
+mod verifying_key_tests {
+    use super::*;
+    use crate::jose::test_keys;
+
+    #[test]
+    fn test_sign_and_verify() {
+        let cases = [
+            (test_keys::RSA_PKCS8_PEM, JwsAlgorithm::RS512),
+            (test_keys::RSA_PKCS8_PEM, JwsAlgorithm::PS256),
+            (test_keys::RSA_PKCS1_PEM, JwsAlgorithm::PS384),
+            (test_keys::RSA_PKCS8_PEM, JwsAlgorithm::PS512),
+            (test_keys::P256_PKCS8_PEM, JwsAlgorithm::ES256),
+            (test_keys::P384_PKCS8_PEM, JwsAlgorithm::ES384),
+            (test_keys::ED25519_PKCS8_PEM, JwsAlgorithm::EdDSA),
+        ];
+        for (pem, alg) in cases {
+            let signing_key = SigningKey::from_pem(pem).unwrap();
+            let key = VerifyingKey::from(&signing_key);
+            let signature = signing_key.sign(alg, b"payload").unwrap();
+            assert_eq!(key.verify(alg, b"payload", &signature), Ok(()), "{alg:?}");
+            assert_eq!(
+                key.verify(alg, b"tampered", &signature),
+                Err(JoseError::InvalidSignature),
+                "{alg:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_verify_hmac() {
+        let signing_key = SigningKey::from_secret(test_keys::HMAC_SECRET);
+        let signature = signing_key.sign(JwsAlgorithm::HS384, b"payload").unwrap();
+        let key = VerifyingKey::from_secret(test_keys::HMAC_SECRET);
+        assert_eq!(
+            key.verify(JwsAlgorithm::HS384, b"payload", &signature),
+            Ok(())
+        );
+        assert_eq!(
+            VerifyingKey::from_secret(&[0; 64]).verify(JwsAlgorithm::HS384, b"payload", &signature),
+            Err(JoseError::InvalidSignature)
+        );
+        assert_eq!(
+            key.verify(JwsAlgorithm::HS256, b"payload", &signature),
+            Err(JoseError::InvalidSignature)
+        );
+    }
+
+    #[test]
+    fn test_verify_short_hmac_key() {
+        let key = VerifyingKey::from_secret(&test_keys::HMAC_SECRET[..48]);
+        let signature = SigningKey::from_secret(test_keys::HMAC_SECRET)
+            .sign(JwsAlgorithm::HS512, b"payload")
+            .unwrap();
+        assert_eq!(
+            key.verify(JwsAlgorithm::HS512, b"payload", &signature),
+            Err(JoseError::InvalidKey(
+                "HS512 requires an HMAC key of 64 bytes or more".into()
+            ))
+        );
+    }
+
+    #[test]
+    fn test_pss_is_not_pkcs1() {
+        let signing_key = SigningKey::from_pem(test_keys::RSA_PKCS8_PEM).unwrap();
+        let key = VerifyingKey::from(&signing_key);
+        let signature = signing_key.sign(JwsAlgorithm::PS256, b"payload").unwrap();
+        assert_eq!(
+            key.verify(JwsAlgorithm::RS256, b"payload", &signature),
+            Err(JoseError::InvalidSignature)
+        );
+        // PSS signatures are randomized.
+        assert_ne!(
+            signature,
+            signing_key.sign(JwsAlgorithm::PS256, b"payload").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_verify_rsa_key_too_small() {
+        let small = rsa::RsaPublicKey::new(
+            rsa::BigUint::from_bytes_be(&[0xc5; 128]),
+            rsa::BigUint::from(65537u32),
+        )
+        .unwrap();
+        assert!(matches!(
+            VerifyingKey::Rsa(Box::new(small)).verify(JwsAlgorithm::RS256, b"payload", &[0; 128]),
+            Err(JoseError::InvalidKey(_))
+        ));
+    }
+
+    #[test]
+    fn test_verify_algorithm_mismatch() {
+        let signing_key = SigningKey::from_pem(test_keys::RSA_PKCS8_PEM).unwrap();
+        let key = VerifyingKey::from(&signing_key);
+        assert_eq!(
+            key.verify(JwsAlgorithm::HS256, b"payload", b"signature"),
+            Err(JoseError::AlgorithmMismatch(JwsAlgorithm::HS256))
+        );
+    }
+}
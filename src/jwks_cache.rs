@@ -0,0 +1,293 @@
+use crate::http::{self, AsyncHttpClient, HttpClient, RequestError};
+use crate::{Jwk, JwkSet};
+use std::error::Error;
+use std::fmt::Display;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+// Minimum time between two fetches triggered by an unknown `kid`.
+pub const DEFAULT_MIN_REFRESH_INTERVAL: Duration = Duration::from_secs(60);
+
+#[derive(Debug)]
+pub enum JwksCacheError<E> {
+    Request(RequestError<E>),
+    // no set is cached and the last fetch, which failed, is too recent to
+    // try again.
+    RateLimited,
+}
+
+impl<E> Display for JwksCacheError<E>
+where
+    E: Display,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            JwksCacheError::Request(e) => write!(f, "JwksCacheError::Request({e})"),
+            JwksCacheError::RateLimited => write!(f, "JwksCacheError::RateLimited"),
+        }
+    }
+}
+
+impl<E> Error for JwksCacheError<E>
+where
+    E: Error + 'static,
+{
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            JwksCacheError::Request(e) => Some(e),
+            JwksCacheError::RateLimited => None,
+        }
+    }
+}
+
+impl<E> From<RequestError<E>> for JwksCacheError<E> {
+    fn from(e: RequestError<E>) -> Self {
+        JwksCacheError::Request(e)
+    }
+}
+
+enum CacheLookup {
+    Cached(JwkSet),
+    Fetch,
+    RateLimited,
+}
+
+#[derive(Debug, Default)]
+struct CacheState {
+    jwks: Option<JwkSet>,
+    last_fetch: Option<Instant>,
+}
+
+// JWK set fetched from a `jwks_uri` and kept until a token names a key it
+// does not hold, which usually means the server rotated its keys. Refetches
+// are rate limited, whether they succeed or not, so that tokens with made
+// up `kid` values or a failing server cannot make us hammer it.
+#[derive(Debug)]
+pub struct JwksCache {
+    jwks_uri: String,
+    min_refresh_interval: Duration,
+    state: Mutex<CacheState>,
+}
+
+impl JwksCache {
+    pub fn new(jwks_uri: String) -> Self {
+        Self {
+            jwks_uri,
+            min_refresh_interval: DEFAULT_MIN_REFRESH_INTERVAL,
+            state: Mutex::new(CacheState::default()),
+        }
+    }
+
+    pub fn set_min_refresh_interval(&mut self, min_refresh_interval: Duration) {
+        self.min_refresh_interval = min_refresh_interval;
+    }
+
+    pub fn jwks_uri(&self) -> String {
+        self.jwks_uri.clone()
+    }
+
+    // Returns the cached set, fetching it when there is none yet or when
+    // `kid` is not in it, as long as the last fetch is old enough.
+    pub fn get_jwk_set<C>(
+        &self,
+        client: &C,
+        kid: Option<&str>,
+    ) -> Result<JwkSet, JwksCacheError<C::Error>>
+    where
+        C: HttpClient,
+    {
+        match self.lookup(kid) {
+            CacheLookup::Cached(jwks) => return Ok(jwks),
+            CacheLookup::RateLimited => return Err(JwksCacheError::RateLimited),
+            CacheLookup::Fetch => {}
+        }
+        let response = client
+            .execute(http::get_json(&self.jwks_uri))
+            .map_err(RequestError::Transport)?;
+        Ok(self.store(http::parse_json_response(response)?))
+    }
+
+    pub async fn get_jwk_set_async<C>(
+        &self,
+        client: &C,
+        kid: Option<&str>,
+    ) -> Result<JwkSet, JwksCacheError<C::Error>>
+    where
+        C: AsyncHttpClient,
+    {
+        match self.lookup(kid) {
+            CacheLookup::Cached(jwks) => return Ok(jwks),
+            CacheLookup::RateLimited => return Err(JwksCacheError::RateLimited),
+            CacheLookup::Fetch => {}
+        }
+        let response = client
+            .execute(http::get_json(&self.jwks_uri))
+            .await
+            .map_err(RequestError::Transport)?;
+        Ok(self.store(http::parse_json_response(response)?))
+    }
+
+    pub fn get_key<C>(&self, client: &C, kid: &str) -> Result<Option<Jwk>, JwksCacheError<C::Error>>
+    where
+        C: HttpClient,
+    {
+        let jwks = self.get_jwk_set(client, Some(kid))?;
+        Ok(jwks.find_by_key_id(kid).cloned())
+    }
+
+    pub async fn get_key_async<C>(
+        &self,
+        client: &C,
+        kid: &str,
+    ) -> Result<Option<Jwk>, JwksCacheError<C::Error>>
+    where
+        C: AsyncHttpClient,
+    {
+        let jwks = self.get_jwk_set_async(client, Some(kid)).await?;
+        Ok(jwks.find_by_key_id(kid).cloned())
+    }
+
+    // drops the cached set so that the next call fetches it regardless of
+    // the rate limit.
+    pub fn clear(&self) {
+        *self.state.lock().expect("poisoned lock") = CacheState::default();
+    }
+
+    // The lock is not held while fetching, so concurrent misses may fetch
+    // more than once. This is harmless and keeps the async path simple.
+    // A fetch is recorded before it is made, so the rate limit also applies
+    // when it fails, including before any set was cached.
+    fn lookup(&self, kid: Option<&str>) -> CacheLookup {
+        let mut state = self.state.lock().expect("poisoned lock");
+        let rate_limited = state
+            .last_fetch
+            .is_some_and(|t| t.elapsed() < self.min_refresh_interval);
+        match state.jwks.as_ref() {
+            Some(jwks)
+                if rate_limited || kid.is_none_or(|kid| jwks.find_by_key_id(kid).is_some()) =>
+            {
+                return CacheLookup::Cached(jwks.clone());
+            }
+            None if rate_limited => return CacheLookup::RateLimited,
+            _ => {}
+        }
+        state.last_fetch = Some(Instant::now());
+        CacheLookup::Fetch
+    }
+
+    fn store(&self, jwks: JwkSet) -> JwkSet {
+        let mut state = self.state.lock().expect("poisoned lock");
+        state.jwks = Some(jwks.clone());
+        jwks
+    }
+}
+
+#[cfg(test)]
+mod jwks_cache_tests {
+    use super::*;
+    use crate::http::block_on;
+    use crate::{HttpMethod, HttpResponse, MockHttpClient};
+
+    fn jwks(kids: &[&str]) -> HttpResponse {
+        let keys = kids
+            .iter()
+            .map(|kid| serde_json::json!({"kty": "OKP", "crv": "Ed25519", "x": "AAAA", "kid": kid}))
+            .collect::<Vec<_>>();
+        HttpResponse::new(200, serde_json::json!({ "keys": keys }).to_string().into())
+    }
+
+    #[test]
+    fn test_cache_hit() {
+        let client = MockHttpClient::new();
+        client.push_response(jwks(&["k1"]));
+        let cache = JwksCache::new("https://server.example.com/jwks.json".into());
+
+        assert_eq!(cache.get_jwk_set(&client, None).unwrap().keys().len(), 1);
+        assert!(cache.get_key(&client, "k1").unwrap().is_some());
+        let requests = client.get_requests();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].method, HttpMethod::Get);
+        assert_eq!(requests[0].url, "https://server.example.com/jwks.json");
+    }
+
+    #[test]
+    fn test_refresh_on_unknown_kid() {
+        let client = MockHttpClient::new();
+        client.push_response(jwks(&["k1"]));
+        client.push_response(jwks(&["k1", "k2"]));
+        let mut cache = JwksCache::new("https://server.example.com/jwks.json".into());
+        cache.set_min_refresh_interval(Duration::ZERO);
+
+        assert!(cache.get_key(&client, "k1").unwrap().is_some());
+        assert!(cache.get_key(&client, "k2").unwrap().is_some());
+        assert_eq!(client.get_requests().len(), 2);
+    }
+
+    #[test]
+    fn test_refresh_rate_limited() {
+        let client = MockHttpClient::new();
+        client.push_response(jwks(&["k1"]));
+        client.push_response(jwks(&["k1", "k2"]));
+        let cache = JwksCache::new("https://server.example.com/jwks.json".into());
+
+        assert!(cache.get_key(&client, "k1").unwrap().is_some());
+        assert!(cache.get_key(&client, "k2").unwrap().is_none());
+        assert!(cache.get_key(&client, "unknown").unwrap().is_none());
+        assert_eq!(client.get_requests().len(), 1);
+
+        cache.clear();
+        assert!(cache.get_key(&client, "k2").unwrap().is_some());
+        assert_eq!(client.get_requests().len(), 2);
+    }
+
+    #[test]
+    fn test_fetch_error_is_rate_limited() {
+        let client = MockHttpClient::new();
+        client.push_response(HttpResponse::new(503, vec![]));
+        client.push_response(jwks(&["k1"]));
+        let cache = JwksCache::new("https://server.example.com/jwks.json".into());
+
+        assert!(matches!(
+            cache.get_key(&client, "k1"),
+            Err(JwksCacheError::Request(RequestError::UnexpectedStatus(
+                503,
+                _
+            )))
+        ));
+        // nothing is cached, but the server is not asked again right away.
+        assert!(matches!(
+            block_on(cache.get_key_async(&client, "k1")),
+            Err(JwksCacheError::RateLimited)
+        ));
+        assert_eq!(client.get_requests().len(), 1);
+
+        cache.state.lock().unwrap().last_fetch =
+            Instant::now().checked_sub(2 * DEFAULT_MIN_REFRESH_INTERVAL);
+        assert!(block_on(cache.get_key_async(&client, "k1"))
+            .unwrap()
+            .is_some());
+        assert_eq!(client.get_requests().len(), 2);
+    }
+
+    #[test]
+    fn test_failed_refresh_is_rate_limited() {
+        let client = MockHttpClient::new();
+        client.push_response(jwks(&["k1"]));
+        client.push_response(HttpResponse::new(503, vec![]));
+        let cache = JwksCache::new("https://server.example.com/jwks.json".into());
+
+        assert!(cache.get_key(&client, "k1").unwrap().is_some());
+        // pretend the first fetch is old enough to allow a refetch.
+        cache.state.lock().unwrap().last_fetch =
+            Instant::now().checked_sub(2 * DEFAULT_MIN_REFRESH_INTERVAL);
+        assert!(matches!(
+            cache.get_key(&client, "k2"),
+            Err(JwksCacheError::Request(RequestError::UnexpectedStatus(
+                503,
+                _
+            )))
+        ));
+        assert!(cache.get_key(&client, "k2").unwrap().is_none());
+        assert_eq!(client.get_requests().len(), 2);
+    }
+}
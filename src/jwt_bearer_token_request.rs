@@ -0,0 +1,262 @@
+use crate::http::{AsyncHttpClient, HttpClient, RequestError};
+use crate::jose::{self, JoseError, JwsAlgorithm, JwsHeader, SigningKey};
+use crate::utils;
+use crate::{ClientAuth, OAuthParams, Token};
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Value};
+
+pub const JWT_BEARER_GRANT_TYPE: &str = "urn:ietf:params:oauth:grant-type:jwt-bearer";
+
+const DEFAULT_LIFETIME_SECS: u64 = 3600;
+
+// Claim set of a JWT authorization grant (RFC 7523 section 3). `iat` and
+// `exp` are filled in when the assertion is signed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct JwtBearerClaims {
+    iss: String,
+    aud: String,
+    sub: Option<String>,
+    lifetime: u64,
+    extras: Map<String, Value>,
+}
+
+impl JwtBearerClaims {
+    pub fn new(iss: String, aud: String) -> Self {
+        Self {
+            iss,
+            aud,
+            sub: None,
+            lifetime: DEFAULT_LIFETIME_SECS,
+            extras: Map::new(),
+        }
+    }
+
+    pub fn set_subject(&mut self, sub: String) {
+        self.sub = Some(sub);
+    }
+
+    pub fn set_lifetime(&mut self, lifetime: u64) {
+        self.lifetime = lifetime;
+    }
+
+    // claims not defined by RFC 7523 like the `scope` claim used by Google
+    // service accounts.
+    pub fn add_claim(&mut self, name: String, value: Value) {
+        self.extras.insert(name, value);
+    }
+
+    fn to_json(&self, now: u64) -> Value {
+        let mut claims = self.extras.clone();
+        claims.insert("iss".into(), self.iss.clone().into());
+        claims.insert("aud".into(), self.aud.clone().into());
+        if let Some(sub) = &self.sub {
+            claims.insert("sub".into(), sub.clone().into());
+        }
+        claims.insert("iat".into(), now.into());
+        claims.insert("exp".into(), (now + self.lifetime).into());
+        Value::Object(claims)
+    }
+}
+
+// JWT Bearer authorization grant request (RFC 7523 section 2.1).
+#[derive(Debug)]
+pub struct JwtBearerTokenRequest {
+    assertion: String,
+    scope: Option<Vec<String>>,
+    extras: Option<Vec<(String, String)>>,
+    client_auth: Option<ClientAuth>,
+}
+
+impl OAuthParams for JwtBearerTokenRequest {
+    fn get_grant_type(&self) -> Option<String> {
+        JWT_BEARER_GRANT_TYPE.to_owned().into()
+    }
+
+    fn get_assertion(&self) -> Option<String> {
+        Some(self.assertion.clone())
+    }
+
+    fn get_scopes_mut(&mut self) -> Option<&mut Vec<String>> {
+        if self.scope.is_none() {
+            self.scope = Some(vec![])
+        }
+        self.scope.as_mut()
+    }
+
+    fn get_scopes_ref(&self) -> Option<&Vec<String>> {
+        self.scope.as_ref()
+    }
+
+    fn get_extra_params_mut(&mut self) -> Option<&mut Vec<(String, String)>> {
+        if self.extras.is_none() {
+            self.extras = Some(vec![])
+        }
+        self.extras.as_mut()
+    }
+
+    fn get_extra_params_ref(&self) -> Option<&Vec<(String, String)>> {
+        self.extras.as_ref()
+    }
+
+    fn get_client_auth(&self) -> Option<&ClientAuth> {
+        self.client_auth.as_ref()
+    }
+}
+
+impl JwtBearerTokenRequest {
+    pub fn new(assertion: String) -> Self {
+        Self {
+            assertion,
+            scope: None,
+            extras: None,
+            client_auth: None,
+        }
+    }
+
+    pub fn from_claims(
+        claims: &JwtBearerClaims,
+        key: &SigningKey,
+        alg: JwsAlgorithm,
+        key_id: Option<String>,
+    ) -> Result<Self, JoseError> {
+        let now = utils::get_unix_time();
+        let mut header = JwsHeader::new(alg);
+        header.typ = Some("JWT".into());
+        header.kid = key_id;
+        let payload = serde_json::to_vec(&claims.to_json(now))
+            .map_err(|e| JoseError::Serialization(e.to_string()))?;
+        let assertion = jose::encode_compact(&header, &payload, key)?;
+        Ok(Self::new(assertion))
+    }
+
+    // the signed JWT, which OAuthParams::get_assertion also returns.
+    pub fn assertion(&self) -> String {
+        self.assertion.clone()
+    }
+
+    pub fn set_client_auth(&mut self, client_auth: ClientAuth) {
+        self.client_auth = Some(client_auth);
+    }
+
+    pub fn add_scope(&mut self, scope: String) {
+        utils::add_scope(self, scope);
+    }
+
+    pub fn add_scopes<I>(&mut self, scopes: I)
+    where
+        I: IntoIterator<Item = String>,
+    {
+        utils::add_scopes(self, scopes);
+    }
+
+    pub fn add_extra_param(&mut self, key: String, value: String) {
+        utils::add_extra_param(self, key, value);
+    }
+
+    pub fn get_request_params_as_vec(&self) -> Vec<(String, String)> {
+        utils::get_request_params_as_vec(self)
+    }
+
+    pub fn get_headers(&self) -> Vec<(String, String)> {
+        utils::get_headers(self)
+    }
+
+    pub fn get_request_body(&self) -> Vec<u8> {
+        utils::get_request_body(self)
+    }
+
+    pub fn execute<C, T>(&self, client: &C, token_url: &str) -> Result<T, RequestError<C::Error>>
+    where
+        C: HttpClient,
+        T: Token + DeserializeOwned,
+    {
+        utils::execute_token_request(self, client, token_url)
+    }
+
+    pub async fn execute_async<C, T>(
+        &self,
+        client: &C,
+        token_url: &str,
+    ) -> Result<T, RequestError<C::Error>>
+    where
+        C: AsyncHttpClient,
+        T: Token + DeserializeOwned,
+    {
+        utils::execute_token_request_async(self, client, token_url).await
+    }
+}
+
+#[cfg(test)]
+mod jwt_bearer_token_request_tests {
+    use super::*;
+    use crate::jose::test_keys;
+
+    fn decode_part(part: &str) -> Value {
+        serde_json::from_slice(&jose::base64url_decode(part).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_prebuilt_assertion() {
+        let mut request = JwtBearerTokenRequest::new("eyJhbGciOiJFUzI1NiJ9.e30.c2ln".into());
+        request.add_scope("read".into());
+        assert_eq!(
+            String::from_utf8(request.get_request_body()).unwrap(),
+            "grant_type=urn%3Aietf%3Aparams%3Aoauth%3Agrant-type%3Ajwt-bearer\
+             &scope=read&assertion=eyJhbGciOiJFUzI1NiJ9.e30.c2ln"
+        );
+    }
+
+    #[test]
+    fn test_from_claims() {
+        let mut claims = JwtBearerClaims::new(
+            "svc@example.iam.gserviceaccount.com".into(),
+            "https://oauth2.googleapis.com/token".into(),
+        );
+        claims.set_subject("user@example.com".into());
+        claims.set_lifetime(600);
+        claims.add_claim(
+            "scope".into(),
+            "https://www.googleapis.com/auth/drive".into(),
+        );
+        let key = SigningKey::from_pem(test_keys::RSA_PKCS8_PEM).unwrap();
+
+        let request = JwtBearerTokenRequest::from_claims(
+            &claims,
+            &key,
+            JwsAlgorithm::RS256,
+            Some("k1".into()),
+        )
+        .unwrap();
+        let assertion = request.assertion();
+        let parts = assertion.split('.').collect::<Vec<_>>();
+
+        let header = decode_part(parts[0]);
+        assert_eq!(header["alg"], "RS256");
+        assert_eq!(header["kid"], "k1");
+
+        let payload = decode_part(parts[1]);
+        assert_eq!(payload["iss"], "svc@example.iam.gserviceaccount.com");
+        assert_eq!(payload["aud"], "https://oauth2.googleapis.com/token");
+        assert_eq!(payload["sub"], "user@example.com");
+        assert_eq!(payload["scope"], "https://www.googleapis.com/auth/drive");
+        assert_eq!(
+            payload["exp"].as_u64().unwrap() - payload["iat"].as_u64().unwrap(),
+            600
+        );
+
+        let params = request.get_request_params_as_vec();
+        assert!(params.contains(&("grant_type".into(), JWT_BEARER_GRANT_TYPE.into())));
+        assert!(params.contains(&("assertion".into(), assertion)));
+    }
+
+    #[test]
+    fn test_from_claims_algorithm_mismatch() {
+        let claims = JwtBearerClaims::new("iss".into(), "aud".into());
+        let key = SigningKey::from_secret(test_keys::HMAC_SECRET);
+        let res = JwtBearerTokenRequest::from_claims(&claims, &key, JwsAlgorithm::ES256, None);
+        assert_eq!(
+            res.unwrap_err(),
+            JoseError::AlgorithmMismatch(JwsAlgorithm::ES256)
+        );
+    }
+}
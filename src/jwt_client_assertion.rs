@@ -0,0 +1,353 @@
+use crate::jose::{self, JoseError, JwsAlgorithm, JwsHeader, SigningKey};
+use crate::random::Random;
+use crate::utils;
+use crate::{ClientAssertion, ClientAssertionError, ClientAuth};
+use serde::Serialize;
+use std::sync::Arc;
+
+pub const JWT_BEARER_CLIENT_ASSERTION_TYPE: &str =
+    "urn:ietf:params:oauth:client-assertion-type:jwt-bearer";
+
+const DEFAULT_LIFETIME_SECS: u64 = 300;
+
+#[derive(Serialize)]
+struct ClientAssertionClaims<'a> {
+    iss: &'a str,
+    sub: &'a str,
+    aud: &'a str,
+    jti: String,
+    exp: u64,
+    iat: u64,
+}
+
+// JWT used for client authentication (RFC 7523 section 2.2), signed with the
+// client secret (client_secret_jwt) or a private key (private_key_jwt) as
+// described in OpenID Connect Core section 9. A new JWT with fresh `jti`,
+// `iat` and `exp` claims is produced for every request.
+#[derive(Debug, Clone)]
+pub struct JwtClientAssertion {
+    client_id: String,
+    audience: String,
+    alg: JwsAlgorithm,
+    key: SigningKey,
+    key_id: Option<String>,
+    lifetime: u64,
+}
+
+impl JwtClientAssertion {
+    pub fn client_secret_jwt(
+        client_id: String,
+        client_secret: String,
+        audience: String,
+        alg: JwsAlgorithm,
+    ) -> Result<Self, JoseError> {
+        Self::new(
+            client_id,
+            audience,
+            alg,
+            SigningKey::from_secret(client_secret.as_bytes()),
+        )
+    }
+
+    pub fn private_key_jwt(
+        client_id: String,
+        key: SigningKey,
+        audience: String,
+        alg: JwsAlgorithm,
+    ) -> Result<Self, JoseError> {
+        if key.is_symmetric() {
+            return Err(JoseError::AlgorithmMismatch(alg));
+        }
+        Self::new(client_id, audience, alg, key)
+    }
+
+    fn new(
+        client_id: String,
+        audience: String,
+        alg: JwsAlgorithm,
+        key: SigningKey,
+    ) -> Result<Self, JoseError> {
+        key.check_algorithm(alg)?;
+        Ok(Self {
+            client_id,
+            audience,
+            alg,
+            key,
+            key_id: None,
+            lifetime: DEFAULT_LIFETIME_SECS,
+        })
+    }
+
+    pub fn set_key_id(&mut self, key_id: String) {
+        self.key_id = Some(key_id);
+    }
+
+    pub fn set_lifetime(&mut self, lifetime: u64) {
+        self.lifetime = lifetime;
+    }
+
+    pub fn into_client_auth(self) -> ClientAuth {
+        ClientAuth::Assertion {
+            client_id: None,
+            assertion: Arc::new(self),
+        }
+    }
+
+    pub fn create_assertion(&self) -> Result<String, JoseError> {
+        let iat = utils::get_unix_time();
+        let jti = Random::new(&mut rand::thread_rng()).get_octet_sequence(16);
+        let claims = ClientAssertionClaims {
+            iss: &self.client_id,
+            sub: &self.client_id,
+            aud: &self.audience,
+            jti: jose::base64url_encode(&jti),
+            exp: iat + self.lifetime,
+            iat,
+        };
+        let mut header = JwsHeader::new(self.alg);
+        header.typ = Some("JWT".into());
+        header.kid = self.key_id.clone();
+        let payload =
+            serde_json::to_vec(&claims).map_err(|e| JoseError::Serialization(e.to_string()))?;
+        jose::encode_compact(&header, &payload, &self.key)
+    }
+}
+
+impl ClientAssertion for JwtClientAssertion {
+    fn get_auth_method(&self) -> String {
+        if self.key.is_symmetric() {
+            "client_secret_jwt".into()
+        } else {
+            "private_key_jwt".into()
+        }
+    }
+
+    fn get_assertion_type(&self) -> String {
+        JWT_BEARER_CLIENT_ASSERTION_TYPE.into()
+    }
+
+    fn get_assertion(&self) -> Result<String, ClientAssertionError> {
+        self.create_assertion().map_err(ClientAssertionError::new)
+    }
+}
+
+#[cfg(test)]
+mod jwt_client_assertion_tests {
+    use super::*;
+    use crate::jose::test_keys;
+    use crate::{
+        ClientCredentialsGrantAuthTokenRequest, HttpResponse, MockHttpClient, RequestError,
+        TokenResponse,
+    };
+    use hmac::{Hmac, Mac};
+    use serde_json::Value;
+    use sha2::Sha256;
+    use std::error::Error;
+
+    // 64 bytes, long enough for HS512.
+    const CLIENT_SECRET: &str = "gX1fBat3bVk8Zq2Rt6Wy9Cd4Fh7Jm3Np5Sv8Xb2Ee6Gi9Lk4Oq7Tu1Wz5Ac8Df3H";
+
+    fn decode_part(part: &str) -> Value {
+        serde_json::from_slice(&jose::base64url_decode(part).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_client_secret_jwt() {
+        let assertion = JwtClientAssertion::client_secret_jwt(
+            "s6BhdRkqt3".into(),
+            CLIENT_SECRET.into(),
+            "https://server.example.com/token".into(),
+            JwsAlgorithm::HS256,
+        )
+        .unwrap();
+        assert_eq!(assertion.get_auth_method(), "client_secret_jwt");
+
+        let jwt = assertion.get_assertion().unwrap();
+        let parts = jwt.split('.').collect::<Vec<_>>();
+        assert_eq!(parts.len(), 3);
+
+        let header = decode_part(parts[0]);
+        assert_eq!(header["alg"], "HS256");
+        assert_eq!(header["typ"], "JWT");
+
+        let claims = decode_part(parts[1]);
+        assert_eq!(claims["iss"], "s6BhdRkqt3");
+        assert_eq!(claims["sub"], "s6BhdRkqt3");
+        assert_eq!(claims["aud"], "https://server.example.com/token");
+        assert_eq!(
+            claims["exp"].as_u64().unwrap() - claims["iat"].as_u64().unwrap(),
+            DEFAULT_LIFETIME_SECS
+        );
+        assert!(claims["jti"].as_str().unwrap().len() >= 22);
+
+        let mut mac = Hmac::<Sha256>::new_from_slice(CLIENT_SECRET.as_bytes()).unwrap();
+        mac.update(format!("{}.{}", parts[0], parts[1]).as_bytes());
+        mac.verify_slice(&jose::base64url_decode(parts[2]).unwrap())
+            .unwrap();
+    }
+
+    #[test]
+    fn test_client_secret_jwt_rejects_short_secret() {
+        let res = JwtClientAssertion::client_secret_jwt(
+            "s6BhdRkqt3".into(),
+            "gX1fBat3bV".into(),
+            "https://server.example.com/token".into(),
+            JwsAlgorithm::HS256,
+        );
+        assert!(matches!(res, Err(JoseError::InvalidKey(_))));
+
+        // long enough for HS256 but not for HS512.
+        let res = JwtClientAssertion::client_secret_jwt(
+            "s6BhdRkqt3".into(),
+            CLIENT_SECRET[..32].into(),
+            "https://server.example.com/token".into(),
+            JwsAlgorithm::HS512,
+        );
+        assert!(matches!(res, Err(JoseError::InvalidKey(_))));
+    }
+
+    #[test]
+    fn test_jti_is_unique() {
+        let assertion = JwtClientAssertion::client_secret_jwt(
+            "s6BhdRkqt3".into(),
+            CLIENT_SECRET.into(),
+            "https://server.example.com/token".into(),
+            JwsAlgorithm::HS512,
+        )
+        .unwrap();
+        let first = decode_part(
+            assertion
+                .get_assertion()
+                .unwrap()
+                .split('.')
+                .nth(1)
+                .unwrap(),
+        );
+        let second = decode_part(
+            assertion
+                .get_assertion()
+                .unwrap()
+                .split('.')
+                .nth(1)
+                .unwrap(),
+        );
+        assert_ne!(first["jti"], second["jti"]);
+    }
+
+    #[test]
+    fn test_private_key_jwt() {
+        let key = SigningKey::from_pem(test_keys::P256_PKCS8_PEM).unwrap();
+        let mut assertion = JwtClientAssertion::private_key_jwt(
+            "s6BhdRkqt3".into(),
+            key,
+            "https://server.example.com/token".into(),
+            JwsAlgorithm::ES256,
+        )
+        .unwrap();
+        assertion.set_key_id("key-1".into());
+        assert_eq!(assertion.get_auth_method(), "private_key_jwt");
+
+        let jwt = assertion.get_assertion().unwrap();
+        let parts = jwt.split('.').collect::<Vec<_>>();
+        let header = decode_part(parts[0]);
+        assert_eq!(header["alg"], "ES256");
+        assert_eq!(header["kid"], "key-1");
+        assert_eq!(jose::base64url_decode(parts[2]).unwrap().len(), 64);
+    }
+
+    #[test]
+    fn test_private_key_jwt_rejects_mismatched_alg() {
+        let key = SigningKey::from_pem(test_keys::ED25519_PKCS8_PEM).unwrap();
+        let res = JwtClientAssertion::private_key_jwt(
+            "s6BhdRkqt3".into(),
+            key,
+            "https://server.example.com/token".into(),
+            JwsAlgorithm::RS256,
+        );
+        assert_eq!(
+            res.unwrap_err(),
+            JoseError::AlgorithmMismatch(JwsAlgorithm::RS256)
+        );
+
+        let res = JwtClientAssertion::private_key_jwt(
+            "s6BhdRkqt3".into(),
+            SigningKey::from_secret(CLIENT_SECRET.as_bytes()),
+            "https://server.example.com/token".into(),
+            JwsAlgorithm::HS256,
+        );
+        assert_eq!(
+            res.unwrap_err(),
+            JoseError::AlgorithmMismatch(JwsAlgorithm::HS256)
+        );
+    }
+
+    #[test]
+    fn test_token_request_params() {
+        let key = SigningKey::from_pem(test_keys::ED25519_PKCS8_PEM).unwrap();
+        let assertion = JwtClientAssertion::private_key_jwt(
+            "s6BhdRkqt3".into(),
+            key,
+            "https://server.example.com/token".into(),
+            JwsAlgorithm::EdDSA,
+        )
+        .unwrap();
+        let mut request = ClientCredentialsGrantAuthTokenRequest::new();
+        request.set_client_auth(assertion.into_client_auth());
+
+        let params = request.get_request_params_as_vec();
+        assert!(params.contains(&(
+            "client_assertion_type".into(),
+            JWT_BEARER_CLIENT_ASSERTION_TYPE.into()
+        )));
+        // a new assertion is only produced when the request is sent.
+        assert!(!params.iter().any(|(k, _)| k == "client_assertion"));
+
+        let client = MockHttpClient::new();
+        client.push_response(HttpResponse::new(
+            200,
+            br#"{"access_token": "2YotnFZFEjr1zCsicMWpAA", "token_type": "Bearer"}"#.to_vec(),
+        ));
+        request
+            .execute::<_, TokenResponse>(&client, "https://server.example.com/token")
+            .unwrap();
+        let sent = crate::decode_form_params(&client.get_requests()[0].body).unwrap();
+        assert!(sent.iter().any(|(k, _)| k == "client_assertion"));
+    }
+
+    #[derive(Debug)]
+    struct FailingAssertion;
+
+    impl ClientAssertion for FailingAssertion {
+        fn get_auth_method(&self) -> String {
+            "private_key_jwt".into()
+        }
+
+        fn get_assertion_type(&self) -> String {
+            JWT_BEARER_CLIENT_ASSERTION_TYPE.into()
+        }
+
+        fn get_assertion(&self) -> Result<String, ClientAssertionError> {
+            Err(ClientAssertionError::new(JoseError::Signing(
+                "hardware key unavailable".into(),
+            )))
+        }
+    }
+
+    #[test]
+    fn test_assertion_error_is_returned() {
+        let mut request = ClientCredentialsGrantAuthTokenRequest::new();
+        request.set_client_auth(ClientAuth::Assertion {
+            client_id: None,
+            assertion: Arc::new(FailingAssertion),
+        });
+        let client = MockHttpClient::new();
+        match request.execute::<_, TokenResponse>(&client, "https://server.example.com/token") {
+            Err(RequestError::ClientAssertion(e)) => assert_eq!(
+                e.source().and_then(|e| e.downcast_ref::<JoseError>()),
+                Some(&JoseError::Signing("hardware key unavailable".into()))
+            ),
+            res => panic!("unexpected result: {res:?}"),
+        }
+        assert!(client.get_requests().is_empty());
+    }
+}
@@ -1,21 +1,77 @@
+mod audience;
 mod auth_code;
 mod auth_token_error;
+mod bearer_error;
+mod client_auth;
 mod client_cred_grant_token_request;
 mod code_verifier;
+mod device_access_token_request;
+mod device_authorization_request;
+mod http;
 mod internal_traits;
+mod introspection_request;
+mod jose;
+mod jwks_cache;
+mod jwt_bearer_token_request;
+mod jwt_client_assertion;
+mod oidc;
 mod owner_password_token_request;
+mod random;
 mod refresh_token_request;
+mod request_validation;
+mod revocation_request;
+mod saml2_bearer_token_request;
+mod scope;
+mod server_metadata;
+mod service_account_key;
+mod state;
+mod token_exchange_request;
+mod token_response;
 mod utils;
 
 pub use auth_code::{
-    AuthCodeAccessTokenRequest, AuthCodeError, AuthCodeErrorKind, AuthCodeRequest, AuthCodeToken,
+    AuthCodeAccessTokenRequest, AuthCodeCallbackError, AuthCodeError, AuthCodeErrorKind,
+    AuthCodeRequest, AuthCodeResponse, AuthCodeToken,
 };
 pub use auth_token_error::{AuthTokenError, AuthTokenErrorKind};
+pub use bearer_error::{BearerError, BearerErrorKind};
+pub use client_auth::{ClientAssertion, ClientAssertionError, ClientAuth, StaticClientAssertion};
 pub use client_cred_grant_token_request::ClientCredentialsGrantAuthTokenRequest;
-pub use code_verifier::{ChallengMethod, CodeVerifier};
+pub use code_verifier::{
+    ChallengMethod, CodeVerifier, CodeVerifierError, PkcePolicy, PkceVerificationError,
+    CODE_VERIFIER_MAX_LEN, CODE_VERIFIER_MIN_LEN,
+};
+pub use device_access_token_request::{DeviceAccessTokenRequest, DEVICE_CODE_GRANT_TYPE};
+pub use device_authorization_request::{DeviceAuthorizationRequest, DeviceAuthorizationResponse};
+pub use http::{
+    AsyncHttpClient, HttpClient, HttpMethod, HttpRequest, HttpResponse, MockHttpClient,
+    MockHttpClientError, RequestError,
+};
 pub use internal_traits::OAuthParams;
+pub use introspection_request::{IntrospectionRequest, IntrospectionResponse};
+pub use jose::{JoseError, Jwk, JwkSet, JwsAlgorithm, SigningKey, VerifyingKey};
+pub use jwks_cache::{JwksCache, JwksCacheError, DEFAULT_MIN_REFRESH_INTERVAL};
+pub use jwt_bearer_token_request::{JwtBearerClaims, JwtBearerTokenRequest, JWT_BEARER_GRANT_TYPE};
+pub use jwt_client_assertion::{JwtClientAssertion, JWT_BEARER_CLIENT_ASSERTION_TYPE};
+pub use oidc::{
+    Address, ClaimsRequest, DisplayMode, IdTokenClaims, IdTokenError, IdTokenVerifier,
+    IndividualClaimRequest, Prompt, PromptError, StandardClaims, UserInfo, UserInfoError,
+    UserInfoRequest, DEFAULT_LEEWAY_SECS,
+};
 pub use owner_password_token_request::OwnerPasswordAccessTokenRequest;
 pub use refresh_token_request::RefreshTokenRequest;
+pub use request_validation::UnsupportedCapability;
+pub use revocation_request::{RevocationRequest, TokenTypeHint};
+pub use saml2_bearer_token_request::{
+    Saml2BearerTokenRequest, Saml2ClientAssertion, SAML2_BEARER_CLIENT_ASSERTION_TYPE,
+    SAML2_BEARER_GRANT_TYPE,
+};
+pub use server_metadata::{DiscoveryError, MetadataError, ServerMetadata, WellKnown};
+pub use service_account_key::{ServiceAccountKey, ServiceAccountKeyError};
+pub use state::{Nonce, RandomValueError, State, DEFAULT_ENTROPY_BYTES, MIN_ENTROPY_BYTES};
+pub use token_exchange_request::{TokenExchangeRequest, TokenType, TOKEN_EXCHANGE_GRANT_TYPE};
+pub use token_response::TokenResponse;
+pub use utils::{decode_form_params, FormDecodeError};
 
 pub trait Token {
     fn set_refresh_token(&mut self, refresh_token: String);
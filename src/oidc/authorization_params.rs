@@ -0,0 +1,241 @@
+use serde_json::{Map, Value};
+use std::error::Error;
+use std::fmt::Display;
+
+// Values of the `prompt` parameter (OpenID Connect Core 1.0 section 3.1.2.1).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Prompt {
+    None,
+    Login,
+    Consent,
+    SelectAccount,
+}
+
+impl Prompt {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Prompt::None => "none",
+            Prompt::Login => "login",
+            Prompt::Consent => "consent",
+            Prompt::SelectAccount => "select_account",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PromptError {
+    Empty,
+    // `none` must not be combined with any other value.
+    NoneWithOtherValues,
+}
+
+impl Display for PromptError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PromptError::Empty => write!(f, "PromptError::Empty"),
+            PromptError::NoneWithOtherValues => write!(f, "PromptError::NoneWithOtherValues"),
+        }
+    }
+}
+
+impl Error for PromptError {}
+
+// Space separated `prompt` value, without duplicates.
+pub(crate) fn prompt_to_string(prompt: &[Prompt]) -> Result<String, PromptError> {
+    if prompt.is_empty() {
+        return Err(PromptError::Empty);
+    }
+    if prompt.contains(&Prompt::None) && prompt.iter().any(|p| *p != Prompt::None) {
+        return Err(PromptError::NoneWithOtherValues);
+    }
+    let mut values: Vec<&str> = vec![];
+    for p in prompt {
+        if !values.contains(&p.as_str()) {
+            values.push(p.as_str());
+        }
+    }
+    Ok(values.join(" "))
+}
+
+// Values of the `display` parameter (OpenID Connect Core 1.0 section 3.1.2.1).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DisplayMode {
+    Page,
+    Popup,
+    Touch,
+    Wap,
+}
+
+impl DisplayMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DisplayMode::Page => "page",
+            DisplayMode::Popup => "popup",
+            DisplayMode::Touch => "touch",
+            DisplayMode::Wap => "wap",
+        }
+    }
+}
+
+// Request for a single claim (OpenID Connect Core 1.0 section 5.5.1). A
+// request without any member is sent as `null`, meaning a voluntary claim.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IndividualClaimRequest {
+    essential: Option<bool>,
+    value: Option<Value>,
+    values: Option<Vec<Value>>,
+}
+
+impl IndividualClaimRequest {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn essential() -> Self {
+        let mut request = Self::new();
+        request.set_essential(true);
+        request
+    }
+
+    pub fn set_essential(&mut self, essential: bool) {
+        self.essential = Some(essential);
+    }
+
+    pub fn set_value(&mut self, value: Value) {
+        self.value = Some(value);
+    }
+
+    pub fn set_values(&mut self, values: Vec<Value>) {
+        self.values = Some(values);
+    }
+
+    fn to_value(&self) -> Value {
+        let mut object = Map::new();
+        if let Some(essential) = self.essential {
+            object.insert("essential".into(), essential.into());
+        }
+        if let Some(ref value) = self.value {
+            object.insert("value".into(), value.clone());
+        }
+        if let Some(ref values) = self.values {
+            object.insert("values".into(), values.clone().into());
+        }
+        if object.is_empty() {
+            return Value::Null;
+        }
+        Value::Object(object)
+    }
+}
+
+// Builder for the `claims` request parameter (OpenID Connect Core 1.0
+// section 5.5).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ClaimsRequest {
+    userinfo: Vec<(String, IndividualClaimRequest)>,
+    id_token: Vec<(String, IndividualClaimRequest)>,
+}
+
+impl ClaimsRequest {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add_userinfo_claim(&mut self, name: String, request: IndividualClaimRequest) {
+        self.userinfo.push((name, request));
+    }
+
+    pub fn add_id_token_claim(&mut self, name: String, request: IndividualClaimRequest) {
+        self.id_token.push((name, request));
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.userinfo.is_empty() && self.id_token.is_empty()
+    }
+
+    pub fn to_json(&self) -> String {
+        let members = |claims: &[(String, IndividualClaimRequest)]| {
+            claims
+                .iter()
+                .map(|(name, request)| (name.clone(), request.to_value()))
+                .collect::<Map<_, _>>()
+        };
+        let mut object = Map::new();
+        if !self.userinfo.is_empty() {
+            object.insert("userinfo".into(), members(&self.userinfo).into());
+        }
+        if !self.id_token.is_empty() {
+            object.insert("id_token".into(), members(&self.id_token).into());
+        }
+        Value::Object(object).to_string()
+    }
+}
+
+#[cfg(test)]
+mod authorization_params_tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_prompt_to_string() {
+        assert_eq!(prompt_to_string(&[Prompt::None]), Ok("none".to_owned()));
+        assert_eq!(
+            prompt_to_string(&[Prompt::Login, Prompt::Consent, Prompt::Login]),
+            Ok("login consent".to_owned())
+        );
+        assert_eq!(
+            prompt_to_string(&[Prompt::SelectAccount]),
+            Ok("select_account".to_owned())
+        );
+        assert_eq!(
+            prompt_to_string(&[Prompt::None, Prompt::Login]),
+            Err(PromptError::NoneWithOtherValues)
+        );
+        assert_eq!(prompt_to_string(&[]), Err(PromptError::Empty));
+    }
+
+    #[test]
+    fn test_claims_request() {
+        // OpenID Connect Core 1.0 section 5.5 example.
+        let mut claims = ClaimsRequest::new();
+        claims.add_userinfo_claim("given_name".into(), IndividualClaimRequest::essential());
+        claims.add_userinfo_claim("nickname".into(), IndividualClaimRequest::new());
+        let mut picture = IndividualClaimRequest::new();
+        picture.set_essential(false);
+        claims.add_userinfo_claim("picture".into(), picture);
+        let mut sub = IndividualClaimRequest::new();
+        sub.set_value(json!("248289761001"));
+        claims.add_id_token_claim("sub".into(), sub);
+        let mut acr = IndividualClaimRequest::essential();
+        acr.set_values(vec![
+            json!("urn:mace:incommon:iap:silver"),
+            json!("urn:mace:incommon:iap:bronze"),
+        ]);
+        claims.add_id_token_claim("acr".into(), acr);
+
+        let parsed: Value = serde_json::from_str(&claims.to_json()).unwrap();
+        assert_eq!(
+            parsed,
+            json!({
+                "userinfo": {
+                    "given_name": {"essential": true},
+                    "nickname": null,
+                    "picture": {"essential": false}
+                },
+                "id_token": {
+                    "sub": {"value": "248289761001"},
+                    "acr": {
+                        "essential": true,
+                        "values": ["urn:mace:incommon:iap:silver", "urn:mace:incommon:iap:bronze"]
+                    }
+                }
+            })
+        );
+    }
+
+    #[test]
+    fn test_claims_request_empty() {
+        let claims = ClaimsRequest::new();
+        assert!(claims.is_empty());
+        assert_eq!(claims.to_json(), "{}");
+    }
+}
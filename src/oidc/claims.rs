@@ -0,0 +1,142 @@
+use serde::{self, Deserialize};
+
+// Standard claims (OpenID Connect Core 1.0 section 5.1) shared by the ID
+// token and the UserInfo response. `sub` is not part of it since it is
+// required in both and is kept by them directly.
+#[derive(Debug, Clone, Default, Deserialize, PartialEq, Eq)]
+pub struct StandardClaims {
+    name: Option<String>,
+    given_name: Option<String>,
+    family_name: Option<String>,
+    middle_name: Option<String>,
+    nickname: Option<String>,
+    preferred_username: Option<String>,
+    profile: Option<String>,
+    picture: Option<String>,
+    website: Option<String>,
+    email: Option<String>,
+    email_verified: Option<bool>,
+    gender: Option<String>,
+    birthdate: Option<String>,
+    zoneinfo: Option<String>,
+    locale: Option<String>,
+    phone_number: Option<String>,
+    phone_number_verified: Option<bool>,
+    address: Option<Address>,
+    updated_at: Option<u64>,
+}
+
+impl StandardClaims {
+    pub fn name(&self) -> Option<String> {
+        self.name.clone()
+    }
+
+    pub fn given_name(&self) -> Option<String> {
+        self.given_name.clone()
+    }
+
+    pub fn family_name(&self) -> Option<String> {
+        self.family_name.clone()
+    }
+
+    pub fn middle_name(&self) -> Option<String> {
+        self.middle_name.clone()
+    }
+
+    pub fn nickname(&self) -> Option<String> {
+        self.nickname.clone()
+    }
+
+    pub fn preferred_username(&self) -> Option<String> {
+        self.preferred_username.clone()
+    }
+
+    pub fn profile(&self) -> Option<String> {
+        self.profile.clone()
+    }
+
+    pub fn picture(&self) -> Option<String> {
+        self.picture.clone()
+    }
+
+    pub fn website(&self) -> Option<String> {
+        self.website.clone()
+    }
+
+    pub fn email(&self) -> Option<String> {
+        self.email.clone()
+    }
+
+    pub fn email_verified(&self) -> Option<bool> {
+        self.email_verified
+    }
+
+    pub fn gender(&self) -> Option<String> {
+        self.gender.clone()
+    }
+
+    pub fn birthdate(&self) -> Option<String> {
+        self.birthdate.clone()
+    }
+
+    pub fn zoneinfo(&self) -> Option<String> {
+        self.zoneinfo.clone()
+    }
+
+    pub fn locale(&self) -> Option<String> {
+        self.locale.clone()
+    }
+
+    pub fn phone_number(&self) -> Option<String> {
+        self.phone_number.clone()
+    }
+
+    pub fn phone_number_verified(&self) -> Option<bool> {
+        self.phone_number_verified
+    }
+
+    pub fn address(&self) -> Option<Address> {
+        self.address.clone()
+    }
+
+    pub fn updated_at(&self) -> Option<u64> {
+        self.updated_at
+    }
+}
+
+// Address claim (OpenID Connect Core 1.0 section 5.1.1).
+#[derive(Debug, Clone, Default, Deserialize, PartialEq, Eq)]
+pub struct Address {
+    formatted: Option<String>,
+    street_address: Option<String>,
+    locality: Option<String>,
+    region: Option<String>,
+    postal_code: Option<String>,
+    country: Option<String>,
+}
+
+impl Address {
+    pub fn formatted(&self) -> Option<String> {
+        self.formatted.clone()
+    }
+
+    pub fn street_address(&self) -> Option<String> {
+        self.street_address.clone()
+    }
+
+    pub fn locality(&self) -> Option<String> {
+        self.locality.clone()
+    }
+
+    pub fn region(&self) -> Option<String> {
+        self.region.clone()
+    }
+
+    pub fn postal_code(&self) -> Option<String> {
+        self.postal_code.clone()
+    }
+
+    pub fn country(&self) -> Option<String> {
+        self.country.clone()
+    }
+}
@@ -0,0 +1,562 @@
+use super::StandardClaims;
+use crate::audience::Audience;
+use crate::jose::{CompactJws, JoseError, JwkSet, JwsAlgorithm, VerifyingKey};
+use crate::{utils, AuthCodeRequest, Nonce, OAuthParams, ServerMetadata};
+use serde::{self, Deserialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt::Display;
+
+// Clock skew tolerated when checking `exp`, `iat` and `auth_time`.
+pub const DEFAULT_LEEWAY_SECS: u64 = 60;
+
+// Claims of an ID token (OpenID Connect Core 1.0 section 2). Claims which
+// are neither defined there nor standard claims are kept in `extras`.
+#[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
+pub struct IdTokenClaims {
+    iss: String,
+    sub: String,
+    aud: Audience,
+    exp: u64,
+    iat: u64,
+    auth_time: Option<u64>,
+    nonce: Option<String>,
+    acr: Option<String>,
+    amr: Option<Vec<String>>,
+    azp: Option<String>,
+    #[serde(flatten)]
+    standard_claims: StandardClaims,
+    #[serde(flatten)]
+    extras: HashMap<String, Value>,
+}
+
+impl IdTokenClaims {
+    pub fn iss(&self) -> String {
+        self.iss.clone()
+    }
+
+    pub fn sub(&self) -> String {
+        self.sub.clone()
+    }
+
+    pub fn aud(&self) -> Vec<String> {
+        self.aud.to_vec()
+    }
+
+    pub fn exp(&self) -> u64 {
+        self.exp
+    }
+
+    pub fn iat(&self) -> u64 {
+        self.iat
+    }
+
+    pub fn auth_time(&self) -> Option<u64> {
+        self.auth_time
+    }
+
+    pub fn nonce(&self) -> Option<String> {
+        self.nonce.clone()
+    }
+
+    pub fn acr(&self) -> Option<String> {
+        self.acr.clone()
+    }
+
+    pub fn amr(&self) -> Option<Vec<String>> {
+        self.amr.clone()
+    }
+
+    pub fn azp(&self) -> Option<String> {
+        self.azp.clone()
+    }
+
+    pub fn standard_claims(&self) -> StandardClaims {
+        self.standard_claims.clone()
+    }
+
+    pub fn get_extra_field(&self, name: &str) -> Option<Value> {
+        self.extras.get(name).cloned()
+    }
+
+    pub fn get_extra_fields(&self) -> HashMap<String, Value> {
+        self.extras.clone()
+    }
+}
+
+#[derive(Debug)]
+pub enum IdTokenError {
+    Jose(JoseError),
+    // no key of the JWK set can verify the token, holds the `kid` header.
+    KeyNotFound(Option<String>),
+    InvalidClaims(serde_json::Error),
+    IssuerMismatch { expected: String, found: String },
+    // the client is not an audience, or another audience is not trusted.
+    AudienceMismatch,
+    AuthorizedPartyMismatch,
+    Expired,
+    IssuedInFuture,
+    MissingNonce,
+    NonceMismatch,
+    MissingAuthTime,
+    // the end-user authenticated longer than `max_age` ago.
+    AuthTimeTooOld,
+}
+
+impl Display for IdTokenError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        use IdTokenError::*;
+        match self {
+            Jose(e) => write!(f, "IdTokenError::Jose({e})"),
+            KeyNotFound(kid) => write!(
+                f,
+                "IdTokenError::KeyNotFound({})",
+                kid.as_deref().unwrap_or_default()
+            ),
+            InvalidClaims(e) => write!(f, "IdTokenError::InvalidClaims({e})"),
+            IssuerMismatch { expected, found } => write!(
+                f,
+                "IdTokenError::IssuerMismatch(expected = {expected}, found = {found})"
+            ),
+            AudienceMismatch => write!(f, "IdTokenError::AudienceMismatch"),
+            AuthorizedPartyMismatch => write!(f, "IdTokenError::AuthorizedPartyMismatch"),
+            Expired => write!(f, "IdTokenError::Expired"),
+            IssuedInFuture => write!(f, "IdTokenError::IssuedInFuture"),
+            MissingNonce => write!(f, "IdTokenError::MissingNonce"),
+            NonceMismatch => write!(f, "IdTokenError::NonceMismatch"),
+            MissingAuthTime => write!(f, "IdTokenError::MissingAuthTime"),
+            AuthTimeTooOld => write!(f, "IdTokenError::AuthTimeTooOld"),
+        }
+    }
+}
+
+impl Error for IdTokenError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            IdTokenError::Jose(e) => Some(e),
+            IdTokenError::InvalidClaims(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<JoseError> for IdTokenError {
+    fn from(e: JoseError) -> Self {
+        IdTokenError::Jose(e)
+    }
+}
+
+// ID token validation (OpenID Connect Core 1.0 section 3.1.3.7).
+#[derive(Debug, Clone)]
+pub struct IdTokenVerifier {
+    issuer: String,
+    client_id: String,
+    jwks: JwkSet,
+    allowed_algs: Vec<JwsAlgorithm>,
+    trusted_audiences: Vec<String>,
+    leeway: u64,
+    nonce: Option<String>,
+    max_age: Option<u64>,
+}
+
+impl IdTokenVerifier {
+    // Only RS256 is accepted until `set_allowed_algorithms` says otherwise,
+    // as it is the default `id_token_signed_response_alg`.
+    pub fn new(issuer: String, client_id: String, jwks: JwkSet) -> Self {
+        Self {
+            issuer,
+            client_id,
+            jwks,
+            allowed_algs: vec![JwsAlgorithm::RS256],
+            trusted_audiences: vec![],
+            leeway: DEFAULT_LEEWAY_SECS,
+            nonce: None,
+            max_age: None,
+        }
+    }
+
+    // Takes the issuer and the algorithms from
+    // `id_token_signing_alg_values_supported`. Algorithms this crate does not
+    // implement, like "none", are left out.
+    pub fn from_metadata(metadata: &ServerMetadata, client_id: String, jwks: JwkSet) -> Self {
+        let mut verifier = Self::new(metadata.issuer(), client_id, jwks);
+        let allowed_algs = metadata
+            .id_token_signing_alg_values_supported()
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|alg| serde_json::from_value(alg.as_str().into()).ok())
+            .collect::<Vec<_>>();
+        if !allowed_algs.is_empty() {
+            verifier.set_allowed_algorithms(allowed_algs);
+        }
+        verifier
+    }
+
+    pub fn set_allowed_algorithms(&mut self, allowed_algs: Vec<JwsAlgorithm>) {
+        self.allowed_algs = allowed_algs;
+    }
+
+    pub fn add_trusted_audience(&mut self, audience: String) {
+        self.trusted_audiences.push(audience);
+    }
+
+    pub fn set_leeway(&mut self, leeway: u64) {
+        self.leeway = leeway;
+    }
+
+    // the nonce sent in the authorization request, the token must carry the
+    // same value.
+    pub fn set_nonce(&mut self, nonce: &Nonce) {
+        self.nonce = Some(nonce.secret());
+    }
+
+    pub fn set_max_age(&mut self, max_age: u64) {
+        self.max_age = Some(max_age);
+    }
+
+    // Takes the nonce and max_age from the authorization request the token
+    // answers, so they cannot drift from what was sent.
+    pub fn set_auth_code_request(&mut self, request: &AuthCodeRequest) {
+        self.nonce = request.get_nonce();
+        self.max_age = request.get_max_age().and_then(|m| m.parse().ok());
+    }
+
+    pub fn set_jwks(&mut self, jwks: JwkSet) {
+        self.jwks = jwks;
+    }
+
+    pub fn verify(&self, id_token: &str) -> Result<IdTokenClaims, IdTokenError> {
+        self.verify_at(id_token, utils::get_unix_time())
+    }
+
+    fn verify_at(&self, id_token: &str, now: u64) -> Result<IdTokenClaims, IdTokenError> {
+        let jws = CompactJws::decode(id_token, &self.allowed_algs)?;
+        let key = self.select_key(jws.header.kid.as_deref(), jws.header.alg)?;
+        let payload = jws.verify(&key)?;
+        let claims: IdTokenClaims =
+            serde_json::from_slice(&payload).map_err(IdTokenError::InvalidClaims)?;
+        self.validate_claims(&claims, now)?;
+        Ok(claims)
+    }
+
+    fn select_key(
+        &self,
+        kid: Option<&str>,
+        alg: JwsAlgorithm,
+    ) -> Result<VerifyingKey, IdTokenError> {
+        match self.jwks.find_signing_key(kid, alg) {
+            Some(jwk) => Ok(jwk.to_verifying_key()?),
+            None => Err(IdTokenError::KeyNotFound(kid.map(String::from))),
+        }
+    }
+
+    fn validate_claims(&self, claims: &IdTokenClaims, now: u64) -> Result<(), IdTokenError> {
+        if claims.iss != self.issuer {
+            return Err(IdTokenError::IssuerMismatch {
+                expected: self.issuer.clone(),
+                found: claims.iss.clone(),
+            });
+        }
+        let audiences = claims.aud.to_vec();
+        if !claims.aud.contains(&self.client_id)
+            || audiences
+                .iter()
+                .any(|aud| *aud != self.client_id && !self.trusted_audiences.contains(aud))
+        {
+            return Err(IdTokenError::AudienceMismatch);
+        }
+        match claims.azp.as_deref() {
+            Some(azp) if azp != self.client_id => {
+                return Err(IdTokenError::AuthorizedPartyMismatch)
+            }
+            None if audiences.len() > 1 => return Err(IdTokenError::AuthorizedPartyMismatch),
+            _ => (),
+        }
+        if now > claims.exp.saturating_add(self.leeway) {
+            return Err(IdTokenError::Expired);
+        }
+        if claims.iat > now.saturating_add(self.leeway) {
+            return Err(IdTokenError::IssuedInFuture);
+        }
+        if let Some(ref expected) = self.nonce {
+            match claims.nonce.as_deref() {
+                Some(nonce) if utils::constant_time_eq(nonce.as_bytes(), expected.as_bytes()) => {}
+                Some(_) => return Err(IdTokenError::NonceMismatch),
+                None => return Err(IdTokenError::MissingNonce),
+            }
+        }
+        if let Some(max_age) = self.max_age {
+            let auth_time = claims.auth_time.ok_or(IdTokenError::MissingAuthTime)?;
+            if now
+                > auth_time
+                    .saturating_add(max_age)
+                    .saturating_add(self.leeway)
+            {
+                return Err(IdTokenError::AuthTimeTooOld);
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod id_token_tests {
+    use super::*;
+    use crate::jose::{base64url_encode, encode_compact, test_keys, JwsHeader, SigningKey};
+    use rsa::traits::PublicKeyParts;
+    use serde_json::json;
+
+    const ISSUER: &str = "https://server.example.com";
+    const CLIENT_ID: &str = "s6BhdRkqt3";
+    const NOW: u64 = 1311281970;
+
+    fn signing_key() -> SigningKey {
+        SigningKey::from_pem(test_keys::RSA_PKCS8_PEM).unwrap()
+    }
+
+    fn jwks() -> JwkSet {
+        let VerifyingKey::Rsa(key) = VerifyingKey::from(&signing_key()) else {
+            unreachable!()
+        };
+        let rsa = json!({
+            "kty": "RSA",
+            "use": "sig",
+            "kid": "rsa1",
+            "n": base64url_encode(&key.n().to_bytes_be()),
+            "e": base64url_encode(&key.e().to_bytes_be()),
+        });
+        // RFC 7517 appendix A.1, an encryption key which must be skipped.
+        let ec = json!({
+            "kty": "EC",
+            "crv": "P-256",
+            "x": "MKBCTNIcKUSDii11ySs3526iDZ8AiTo7Tu6KPAqv7D4",
+            "y": "4Etl6SRW2YiLUrN5vfvVHuhp7x8PxltmWWlbbM4IFyM",
+            "use": "enc",
+            "kid": "ec1",
+        });
+        serde_json::from_value(json!({ "keys": [rsa, ec] })).unwrap()
+    }
+
+    fn claims() -> Value {
+        json!({
+            "iss": ISSUER,
+            "sub": "24400320",
+            "aud": CLIENT_ID,
+            "nonce": "n-0S6_WzA2Mj",
+            "exp": NOW + 600,
+            "iat": NOW,
+            "auth_time": NOW - 100,
+            "acr": "urn:mace:incommon:iap:silver",
+            "email": "janedoe@example.com",
+            "email_verified": true,
+            "address": {"country": "US"},
+            "x_tenant": "acme"
+        })
+    }
+
+    fn sign(claims: &Value, alg: JwsAlgorithm, kid: Option<&str>) -> String {
+        let mut header = JwsHeader::new(alg);
+        header.kid = kid.map(String::from);
+        encode_compact(&header, claims.to_string().as_bytes(), &signing_key()).unwrap()
+    }
+
+    fn verifier() -> IdTokenVerifier {
+        let mut verifier = IdTokenVerifier::new(ISSUER.into(), CLIENT_ID.into(), jwks());
+        verifier.set_nonce(&Nonce::from_string("n-0S6_WzA2Mj".into()));
+        verifier
+    }
+
+    #[test]
+    fn test_verify() {
+        let token = sign(&claims(), JwsAlgorithm::RS256, Some("rsa1"));
+        let claims = verifier().verify_at(&token, NOW).unwrap();
+        assert_eq!(claims.iss(), ISSUER);
+        assert_eq!(claims.sub(), "24400320");
+        assert_eq!(claims.aud(), vec![CLIENT_ID.to_owned()]);
+        assert_eq!(claims.nonce(), Some("n-0S6_WzA2Mj".to_owned()));
+        assert_eq!(claims.auth_time(), Some(NOW - 100));
+        assert_eq!(
+            claims.acr(),
+            Some("urn:mace:incommon:iap:silver".to_owned())
+        );
+        assert_eq!(claims.azp(), None);
+        let standard = claims.standard_claims();
+        assert_eq!(standard.email(), Some("janedoe@example.com".to_owned()));
+        assert_eq!(standard.email_verified(), Some(true));
+        assert_eq!(
+            standard.address().and_then(|a| a.country()),
+            Some("US".to_owned())
+        );
+        assert_eq!(claims.get_extra_field("x_tenant"), Some(json!("acme")));
+        assert_eq!(claims.get_extra_fields().len(), 1);
+    }
+
+    #[test]
+    fn test_verify_without_kid() {
+        // the encryption key is ignored, leaving a single candidate.
+        let token = sign(&claims(), JwsAlgorithm::RS256, None);
+        assert!(verifier().verify_at(&token, NOW).is_ok());
+    }
+
+    #[test]
+    fn test_verify_signature_errors() {
+        let token = sign(&claims(), JwsAlgorithm::RS256, Some("unknown"));
+        assert!(matches!(
+            verifier().verify_at(&token, NOW),
+            Err(IdTokenError::KeyNotFound(Some(kid))) if kid == "unknown"
+        ));
+
+        let token = sign(&claims(), JwsAlgorithm::RS512, Some("rsa1"));
+        assert!(matches!(
+            verifier().verify_at(&token, NOW),
+            Err(IdTokenError::Jose(JoseError::AlgorithmNotAllowed(_)))
+        ));
+
+        let token = sign(&claims(), JwsAlgorithm::RS256, Some("rsa1"));
+        let (signing_input, _) = token.rsplit_once('.').unwrap();
+        let mut tampered = claims();
+        tampered["sub"] = json!("admin");
+        let (header, _) = signing_input.split_once('.').unwrap();
+        let forged = format!(
+            "{}.{}.{}",
+            header,
+            base64url_encode(tampered.to_string().as_bytes()),
+            token.rsplit_once('.').unwrap().1
+        );
+        assert!(matches!(
+            verifier().verify_at(&forged, NOW),
+            Err(IdTokenError::Jose(JoseError::InvalidSignature))
+        ));
+    }
+
+    #[test]
+    fn test_verify_claim_errors() {
+        let verify = |claims: Value| {
+            let token = sign(&claims, JwsAlgorithm::RS256, Some("rsa1"));
+            verifier().verify_at(&token, NOW)
+        };
+
+        let mut c = claims();
+        c["iss"] = json!("https://evil.example.com");
+        assert!(matches!(
+            verify(c),
+            Err(IdTokenError::IssuerMismatch { .. })
+        ));
+
+        let mut c = claims();
+        c["aud"] = json!("other");
+        assert!(matches!(verify(c), Err(IdTokenError::AudienceMismatch)));
+
+        let mut c = claims();
+        c["aud"] = json!([CLIENT_ID, "other"]);
+        assert!(matches!(verify(c), Err(IdTokenError::AudienceMismatch)));
+
+        let mut c = claims();
+        c["azp"] = json!("other");
+        assert!(matches!(
+            verify(c),
+            Err(IdTokenError::AuthorizedPartyMismatch)
+        ));
+
+        let mut c = claims();
+        c["exp"] = json!(NOW - DEFAULT_LEEWAY_SECS - 1);
+        assert!(matches!(verify(c), Err(IdTokenError::Expired)));
+
+        let mut c = claims();
+        c["iat"] = json!(NOW + DEFAULT_LEEWAY_SECS + 1);
+        assert!(matches!(verify(c), Err(IdTokenError::IssuedInFuture)));
+
+        let mut c = claims();
+        c["nonce"] = json!("replayed");
+        assert!(matches!(verify(c), Err(IdTokenError::NonceMismatch)));
+
+        let mut c = claims();
+        c.as_object_mut().unwrap().remove("nonce");
+        assert!(matches!(verify(c), Err(IdTokenError::MissingNonce)));
+
+        let mut c = claims();
+        c.as_object_mut().unwrap().remove("sub");
+        assert!(matches!(verify(c), Err(IdTokenError::InvalidClaims(_))));
+    }
+
+    #[test]
+    fn test_verify_leeway_and_audiences() {
+        let mut c = claims();
+        c["exp"] = json!(NOW - 30);
+        c["aud"] = json!([CLIENT_ID, "https://api.example.com"]);
+        c["azp"] = json!(CLIENT_ID);
+        let token = sign(&c, JwsAlgorithm::RS256, Some("rsa1"));
+
+        let mut verifier = verifier();
+        assert!(matches!(
+            verifier.verify_at(&token, NOW),
+            Err(IdTokenError::AudienceMismatch)
+        ));
+        verifier.add_trusted_audience("https://api.example.com".into());
+        assert!(verifier.verify_at(&token, NOW).is_ok());
+        verifier.set_leeway(0);
+        assert!(matches!(
+            verifier.verify_at(&token, NOW),
+            Err(IdTokenError::Expired)
+        ));
+    }
+
+    #[test]
+    fn test_verify_max_age() {
+        let token = sign(&claims(), JwsAlgorithm::RS256, Some("rsa1"));
+        let mut verifier = verifier();
+        verifier.set_leeway(0);
+        verifier.set_max_age(100);
+        assert!(verifier.verify_at(&token, NOW).is_ok());
+        verifier.set_max_age(99);
+        assert!(matches!(
+            verifier.verify_at(&token, NOW),
+            Err(IdTokenError::AuthTimeTooOld)
+        ));
+    }
+
+    #[test]
+    fn test_set_auth_code_request() {
+        let token = sign(&claims(), JwsAlgorithm::RS256, Some("rsa1"));
+        let mut request = AuthCodeRequest::new(CLIENT_ID.into(), "code".into());
+        request.set_nonce(&Nonce::from_string("n-0S6_WzA2Mj".into()));
+        request.set_max_age(99);
+        let mut verifier = IdTokenVerifier::new(ISSUER.into(), CLIENT_ID.into(), jwks());
+        verifier.set_leeway(0);
+        verifier.set_auth_code_request(&request);
+        assert!(matches!(
+            verifier.verify_at(&token, NOW),
+            Err(IdTokenError::AuthTimeTooOld)
+        ));
+
+        request.set_max_age(100);
+        verifier.set_auth_code_request(&request);
+        assert!(verifier.verify_at(&token, NOW).is_ok());
+
+        request.set_nonce(&Nonce::from_string("another-nonce".into()));
+        verifier.set_auth_code_request(&request);
+        assert!(matches!(
+            verifier.verify_at(&token, NOW),
+            Err(IdTokenError::NonceMismatch)
+        ));
+    }
+
+    #[test]
+    fn test_from_metadata() {
+        let metadata = ServerMetadata::from_json(
+            br#"{
+                "issuer": "https://server.example.com",
+                "response_types_supported": ["code"],
+                "id_token_signing_alg_values_supported": ["RS512", "none"]
+            }"#,
+        )
+        .unwrap();
+        let mut verifier = IdTokenVerifier::from_metadata(&metadata, CLIENT_ID.into(), jwks());
+        verifier.set_nonce(&Nonce::from_string("n-0S6_WzA2Mj".into()));
+        let token = sign(&claims(), JwsAlgorithm::RS512, Some("rsa1"));
+        assert!(verifier.verify_at(&token, NOW).is_ok());
+        let token = sign(&claims(), JwsAlgorithm::RS256, Some("rsa1"));
+        assert!(verifier.verify_at(&token, NOW).is_err());
+    }
+}
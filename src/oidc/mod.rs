@@ -0,0 +1,12 @@
+mod authorization_params;
+mod claims;
+mod id_token;
+mod userinfo;
+
+pub(crate) use authorization_params::prompt_to_string;
+pub use authorization_params::{
+    ClaimsRequest, DisplayMode, IndividualClaimRequest, Prompt, PromptError,
+};
+pub use claims::{Address, StandardClaims};
+pub use id_token::{IdTokenClaims, IdTokenError, IdTokenVerifier, DEFAULT_LEEWAY_SECS};
+pub use userinfo::{UserInfo, UserInfoError, UserInfoRequest};
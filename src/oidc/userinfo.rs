@@ -0,0 +1,522 @@
+use super::StandardClaims;
+use crate::audience::Audience;
+use crate::http::{
+    self, AsyncHttpClient, HttpClient, HttpMethod, HttpRequest, HttpResponse, RequestError,
+};
+use crate::jose::{CompactJws, JoseError, JwkSet, JwsAlgorithm};
+use crate::{BearerError, Token};
+use serde::{self, Deserialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt::Display;
+
+// UserInfo Response (OpenID Connect Core 1.0 section 5.3.2). Claims which
+// are not standard claims are kept in `extras`.
+#[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
+pub struct UserInfo {
+    sub: String,
+    #[serde(flatten)]
+    standard_claims: StandardClaims,
+    #[serde(flatten)]
+    extras: HashMap<String, Value>,
+}
+
+impl UserInfo {
+    pub fn sub(&self) -> String {
+        self.sub.clone()
+    }
+
+    pub fn standard_claims(&self) -> StandardClaims {
+        self.standard_claims.clone()
+    }
+
+    pub fn get_extra_field(&self, name: &str) -> Option<Value> {
+        self.extras.get(name).cloned()
+    }
+
+    pub fn get_extra_fields(&self) -> HashMap<String, Value> {
+        self.extras.clone()
+    }
+}
+
+#[derive(Debug)]
+pub enum UserInfoError<E> {
+    Request(RequestError<E>),
+    // the server rejected the access token, as reported in the
+    // `WWW-Authenticate` header (RFC 6750 section 3).
+    Bearer(BearerError),
+    // the response is a signed JWT, and it could not be verified.
+    Jose(JoseError),
+    // no key of the JWK set can verify the response, holds the `kid` header.
+    KeyNotFound(Option<String>),
+    // a signed response was received but no keys were configured.
+    UnexpectedSignedResponse,
+    // keys were configured, so a signed response was asked for, but the
+    // server answered with plain JSON.
+    UnexpectedUnsignedResponse,
+    // the `sub` does not match the one of the ID token, so the response
+    // must not be used (OpenID Connect Core 1.0 section 5.3.2).
+    SubjectMismatch { expected: String, found: String },
+    // the `iss` of a signed response is not the expected issuer.
+    IssuerMismatch { expected: String, found: String },
+    // the `aud` of a signed response does not include the client.
+    AudienceMismatch,
+}
+
+impl<E> Display for UserInfoError<E>
+where
+    E: Display,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        use UserInfoError::*;
+        match self {
+            Request(e) => write!(f, "UserInfoError::Request({e})"),
+            Bearer(e) => write!(f, "UserInfoError::Bearer({e})"),
+            Jose(e) => write!(f, "UserInfoError::Jose({e})"),
+            KeyNotFound(kid) => write!(
+                f,
+                "UserInfoError::KeyNotFound({})",
+                kid.as_deref().unwrap_or_default()
+            ),
+            UnexpectedSignedResponse => write!(f, "UserInfoError::UnexpectedSignedResponse"),
+            UnexpectedUnsignedResponse => {
+                write!(f, "UserInfoError::UnexpectedUnsignedResponse")
+            }
+            SubjectMismatch { expected, found } => write!(
+                f,
+                "UserInfoError::SubjectMismatch(expected = {expected}, found = {found})"
+            ),
+            IssuerMismatch { expected, found } => write!(
+                f,
+                "UserInfoError::IssuerMismatch(expected = {expected}, found = {found})"
+            ),
+            AudienceMismatch => write!(f, "UserInfoError::AudienceMismatch"),
+        }
+    }
+}
+
+impl<E> Error for UserInfoError<E>
+where
+    E: Error + 'static,
+{
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            UserInfoError::Request(e) => Some(e),
+            UserInfoError::Bearer(e) => Some(e),
+            UserInfoError::Jose(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl<E> From<JoseError> for UserInfoError<E> {
+    fn from(e: JoseError) -> Self {
+        UserInfoError::Jose(e)
+    }
+}
+
+// Members of a signed UserInfo response which are checked before use
+// (OpenID Connect Core 1.0 section 5.3.2).
+#[derive(Deserialize)]
+struct SignedResponseClaims {
+    iss: Option<String>,
+    aud: Option<Audience>,
+}
+
+// UserInfo Request (OpenID Connect Core 1.0 section 5.3.1), sent with the
+// access token as a bearer token (RFC 6750 section 2.1).
+#[derive(Debug, Clone)]
+pub struct UserInfoRequest {
+    access_token: String,
+    expected_subject: Option<String>,
+    expected_issuer: Option<String>,
+    expected_audience: Option<String>,
+    jwks: Option<JwkSet>,
+    allowed_algs: Vec<JwsAlgorithm>,
+}
+
+impl UserInfoRequest {
+    pub fn new<T>(token: &T) -> Self
+    where
+        T: Token,
+    {
+        Self {
+            access_token: token.access_token(),
+            expected_subject: None,
+            expected_issuer: None,
+            expected_audience: None,
+            jwks: None,
+            allowed_algs: vec![],
+        }
+    }
+
+    // the `sub` of the ID token, which the response must carry.
+    pub fn set_expected_subject(&mut self, sub: String) {
+        self.expected_subject = Some(sub);
+    }
+
+    // The issuer and client_id which the `iss` and `aud` of a signed
+    // response must match, when the response carries them.
+    pub fn set_expected_issuer(&mut self, issuer: String) {
+        self.expected_issuer = Some(issuer);
+    }
+
+    pub fn set_expected_audience(&mut self, client_id: String) {
+        self.expected_audience = Some(client_id);
+    }
+
+    // Keys and algorithms for `application/jwt` responses, which are only
+    // accepted once this is set. From then on, responses must be signed.
+    pub fn set_signed_response_keys(&mut self, jwks: JwkSet, allowed_algs: Vec<JwsAlgorithm>) {
+        self.jwks = Some(jwks);
+        self.allowed_algs = allowed_algs;
+    }
+
+    pub fn get_headers(&self) -> Vec<(String, String)> {
+        let accept = match self.jwks {
+            Some(_) => "application/jwt",
+            None => "application/json",
+        };
+        vec![
+            (
+                "Authorization".to_owned(),
+                format!("Bearer {}", self.access_token),
+            ),
+            ("Accept".to_owned(), accept.to_owned()),
+        ]
+    }
+
+    fn get_request(&self, userinfo_url: &str) -> HttpRequest {
+        HttpRequest {
+            method: HttpMethod::Get,
+            url: userinfo_url.to_owned(),
+            headers: self.get_headers(),
+            body: vec![],
+        }
+    }
+
+    pub fn execute<C>(
+        &self,
+        client: &C,
+        userinfo_url: &str,
+    ) -> Result<UserInfo, UserInfoError<C::Error>>
+    where
+        C: HttpClient,
+    {
+        let response = client
+            .execute(self.get_request(userinfo_url))
+            .map_err(|e| UserInfoError::Request(RequestError::Transport(e)))?;
+        self.parse_response(response)
+    }
+
+    pub async fn execute_async<C>(
+        &self,
+        client: &C,
+        userinfo_url: &str,
+    ) -> Result<UserInfo, UserInfoError<C::Error>>
+    where
+        C: AsyncHttpClient,
+    {
+        let response = client
+            .execute(self.get_request(userinfo_url))
+            .await
+            .map_err(|e| UserInfoError::Request(RequestError::Transport(e)))?;
+        self.parse_response(response)
+    }
+
+    fn parse_response<E>(&self, response: HttpResponse) -> Result<UserInfo, UserInfoError<E>> {
+        if !(200..=299).contains(&response.status) {
+            if let Some(e) = response
+                .get_header("WWW-Authenticate")
+                .and_then(BearerError::from_www_authenticate)
+            {
+                return Err(UserInfoError::Bearer(e));
+            }
+        }
+        let signed = response
+            .get_header("Content-Type")
+            .is_some_and(|c| c.trim_start().starts_with("application/jwt"));
+        let success = (200..=299).contains(&response.status);
+        if success && !signed && self.jwks.is_some() {
+            return Err(UserInfoError::UnexpectedUnsignedResponse);
+        }
+        let user_info: UserInfo = if signed && success {
+            let payload = self.verify_signed_response(&response.body)?;
+            let claims: SignedResponseClaims = serde_json::from_slice(&payload)
+                .map_err(|e| UserInfoError::Request(RequestError::Parse(e)))?;
+            self.validate_signed_claims(claims)?;
+            serde_json::from_slice(&payload)
+                .map_err(|e| UserInfoError::Request(RequestError::Parse(e)))?
+        } else {
+            http::parse_json_response(response).map_err(UserInfoError::Request)?
+        };
+        if let Some(ref expected) = self.expected_subject {
+            if *expected != user_info.sub {
+                return Err(UserInfoError::SubjectMismatch {
+                    expected: expected.clone(),
+                    found: user_info.sub,
+                });
+            }
+        }
+        Ok(user_info)
+    }
+
+    fn verify_signed_response<E>(&self, body: &[u8]) -> Result<Vec<u8>, UserInfoError<E>> {
+        let jwks = self
+            .jwks
+            .as_ref()
+            .ok_or(UserInfoError::UnexpectedSignedResponse)?;
+        let token = std::str::from_utf8(body)
+            .map_err(|e| JoseError::Serialization(e.to_string()))?
+            .trim();
+        let jws = CompactJws::decode(token, &self.allowed_algs)?;
+        let kid = jws.header.kid.clone();
+        let jwk = jwks
+            .find_signing_key(kid.as_deref(), jws.header.alg)
+            .ok_or(UserInfoError::KeyNotFound(kid))?;
+        Ok(jws.verify(&jwk.to_verifying_key()?)?)
+    }
+
+    fn validate_signed_claims<E>(
+        &self,
+        claims: SignedResponseClaims,
+    ) -> Result<(), UserInfoError<E>> {
+        if let (Some(expected), Some(found)) = (&self.expected_issuer, claims.iss) {
+            if *expected != found {
+                return Err(UserInfoError::IssuerMismatch {
+                    expected: expected.clone(),
+                    found,
+                });
+            }
+        }
+        if let (Some(client_id), Some(aud)) = (&self.expected_audience, claims.aud) {
+            if !aud.contains(client_id) {
+                return Err(UserInfoError::AudienceMismatch);
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod userinfo_tests {
+    use super::*;
+    use crate::jose::{base64url_encode, encode_compact, test_keys, JwsHeader, SigningKey};
+    use crate::{BearerErrorKind, MockHttpClient, TokenResponse, VerifyingKey};
+    use rsa::traits::PublicKeyParts;
+    use serde_json::json;
+
+    const USERINFO_URL: &str = "https://server.example.com/userinfo";
+
+    // OpenID Connect Core 1.0 section 5.3.2 example, with an address and a
+    // custom claim.
+    const USERINFO: &str = r#"{
+        "sub": "248289761001",
+        "name": "Jane Doe",
+        "given_name": "Jane",
+        "family_name": "Doe",
+        "preferred_username": "j.doe",
+        "email": "janedoe@example.com",
+        "picture": "http://example.com/janedoe/me.jpg",
+        "address": {
+            "street_address": "1234 Hollywood Blvd.",
+            "locality": "Los Angeles",
+            "region": "CA",
+            "postal_code": "90210",
+            "country": "US"
+        },
+        "https://example.com/roles": ["admin"]
+    }"#;
+
+    fn new_request() -> UserInfoRequest {
+        let token = TokenResponse::new("SlAV32hkKG".into(), "Bearer".into());
+        UserInfoRequest::new(&token)
+    }
+
+    fn json_response(body: &str) -> HttpResponse {
+        let mut response = HttpResponse::new(200, body.into());
+        response
+            .headers
+            .push(("Content-Type".into(), "application/json".into()));
+        response
+    }
+
+    #[test]
+    fn test_execute() {
+        let client = MockHttpClient::new();
+        client.push_response(json_response(USERINFO));
+        let mut request = new_request();
+        request.set_expected_subject("248289761001".into());
+        let user_info = request.execute(&client, USERINFO_URL).unwrap();
+
+        assert_eq!(user_info.sub(), "248289761001");
+        let claims = user_info.standard_claims();
+        assert_eq!(claims.name(), Some("Jane Doe".to_owned()));
+        assert_eq!(claims.preferred_username(), Some("j.doe".to_owned()));
+        assert_eq!(claims.email_verified(), None);
+        let address = claims.address().unwrap();
+        assert_eq!(
+            address.street_address(),
+            Some("1234 Hollywood Blvd.".to_owned())
+        );
+        assert_eq!(address.postal_code(), Some("90210".to_owned()));
+        assert_eq!(address.formatted(), None);
+        assert_eq!(
+            user_info.get_extra_field("https://example.com/roles"),
+            Some(json!(["admin"]))
+        );
+        assert_eq!(user_info.get_extra_fields().len(), 1);
+
+        let requests = client.get_requests();
+        assert_eq!(requests[0].method, HttpMethod::Get);
+        assert_eq!(requests[0].url, USERINFO_URL);
+        assert!(requests[0]
+            .headers
+            .contains(&("Authorization".into(), "Bearer SlAV32hkKG".into())));
+        assert!(requests[0]
+            .headers
+            .contains(&("Accept".into(), "application/json".into())));
+    }
+
+    #[test]
+    fn test_execute_subject_mismatch() {
+        let client = MockHttpClient::new();
+        client.push_response(json_response(USERINFO));
+        let mut request = new_request();
+        request.set_expected_subject("someone-else".into());
+        match request.execute(&client, USERINFO_URL) {
+            Err(UserInfoError::SubjectMismatch { expected, found }) => {
+                assert_eq!(expected, "someone-else");
+                assert_eq!(found, "248289761001");
+            }
+            res => panic!("unexpected result: {res:?}"),
+        }
+    }
+
+    #[test]
+    fn test_execute_invalid_token() {
+        let client = MockHttpClient::new();
+        let mut response = HttpResponse::new(401, vec![]);
+        response.headers.push((
+            "WWW-Authenticate".into(),
+            r#"Bearer error="invalid_token""#.into(),
+        ));
+        client.push_response(response);
+        match new_request().execute(&client, USERINFO_URL) {
+            Err(UserInfoError::Bearer(e)) => {
+                assert_eq!(e.error(), &BearerErrorKind::InvalidToken)
+            }
+            res => panic!("unexpected result: {res:?}"),
+        }
+
+        // without a bearer challenge the status is all there is.
+        client.push_response(HttpResponse::new(401, vec![]));
+        assert!(matches!(
+            new_request().execute(&client, USERINFO_URL),
+            Err(UserInfoError::Request(RequestError::UnexpectedStatus(
+                401,
+                _
+            )))
+        ));
+    }
+
+    fn signed_response(claims: &str) -> (JwkSet, HttpResponse) {
+        let key = SigningKey::from_pem(test_keys::RSA_PKCS8_PEM).unwrap();
+        let VerifyingKey::Rsa(public) = VerifyingKey::from(&key) else {
+            unreachable!()
+        };
+        let jwks: JwkSet = serde_json::from_value(json!({"keys": [{
+            "kty": "RSA",
+            "kid": "rsa1",
+            "n": base64url_encode(&public.n().to_bytes_be()),
+            "e": base64url_encode(&public.e().to_bytes_be()),
+        }]}))
+        .unwrap();
+        let mut header = JwsHeader::new(JwsAlgorithm::RS256);
+        header.kid = Some("rsa1".into());
+        let jwt = encode_compact(&header, claims.as_bytes(), &key).unwrap();
+        let mut response = HttpResponse::new(200, jwt.into());
+        response.headers.push((
+            "content-type".into(),
+            "application/jwt; charset=utf-8".into(),
+        ));
+        (jwks, response)
+    }
+
+    #[test]
+    fn test_execute_signed_response() {
+        let (jwks, response) = signed_response(USERINFO);
+        let client = MockHttpClient::new();
+        client.push_response(response.clone());
+        let mut request = new_request();
+        request.set_signed_response_keys(jwks.clone(), vec![JwsAlgorithm::RS256]);
+        let user_info = request.execute(&client, USERINFO_URL).unwrap();
+        assert_eq!(user_info.sub(), "248289761001");
+        assert!(client.get_requests()[0]
+            .headers
+            .contains(&("Accept".into(), "application/jwt".into())));
+
+        // not accepted unless keys were configured.
+        client.push_response(response.clone());
+        assert!(matches!(
+            new_request().execute(&client, USERINFO_URL),
+            Err(UserInfoError::UnexpectedSignedResponse)
+        ));
+
+        client.push_response(response);
+        request.set_signed_response_keys(jwks, vec![JwsAlgorithm::ES256]);
+        assert!(matches!(
+            request.execute(&client, USERINFO_URL),
+            Err(UserInfoError::Jose(JoseError::AlgorithmNotAllowed(_)))
+        ));
+    }
+
+    #[test]
+    fn test_execute_signed_response_issuer_and_audience() {
+        let claims = json!({
+            "sub": "248289761001",
+            "iss": "https://server.example.com",
+            "aud": ["s6BhdRkqt3", "other"],
+        })
+        .to_string();
+        let (jwks, response) = signed_response(&claims);
+        let client = MockHttpClient::new();
+        let mut request = new_request();
+        request.set_signed_response_keys(jwks, vec![JwsAlgorithm::RS256]);
+        request.set_expected_issuer("https://server.example.com".into());
+        request.set_expected_audience("s6BhdRkqt3".into());
+        client.push_response(response.clone());
+        assert!(request.execute(&client, USERINFO_URL).is_ok());
+
+        request.set_expected_audience("someone-else".into());
+        client.push_response(response.clone());
+        assert!(matches!(
+            request.execute(&client, USERINFO_URL),
+            Err(UserInfoError::AudienceMismatch)
+        ));
+
+        request.set_expected_issuer("https://other.example.com".into());
+        client.push_response(response);
+        match request.execute(&client, USERINFO_URL) {
+            Err(UserInfoError::IssuerMismatch { expected, found }) => {
+                assert_eq!(expected, "https://other.example.com");
+                assert_eq!(found, "https://server.example.com");
+            }
+            res => panic!("unexpected result: {res:?}"),
+        }
+    }
+
+    #[test]
+    fn test_execute_rejects_unsigned_response() {
+        let (jwks, _) = signed_response(USERINFO);
+        let client = MockHttpClient::new();
+        client.push_response(json_response(USERINFO));
+        let mut request = new_request();
+        request.set_signed_response_keys(jwks, vec![JwsAlgorithm::RS256]);
+        assert!(matches!(
+            request.execute(&client, USERINFO_URL),
+            Err(UserInfoError::UnexpectedUnsignedResponse)
+        ));
+    }
+}
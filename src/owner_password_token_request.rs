@@ -1,5 +1,7 @@
+use crate::http::{AsyncHttpClient, HttpClient, RequestError};
 use crate::utils;
-use crate::OAuthParams;
+use crate::{ClientAuth, OAuthParams, Token};
+use serde::de::DeserializeOwned;
 
 #[derive(Debug)]
 pub struct OwnerPasswordAccessTokenRequest {
@@ -7,6 +9,7 @@ pub struct OwnerPasswordAccessTokenRequest {
     password: String,
     extras: Option<Vec<(String, String)>>,
     scope: Option<Vec<String>>,
+    client_auth: Option<ClientAuth>,
 }
 
 impl OAuthParams for OwnerPasswordAccessTokenRequest {
@@ -43,6 +46,10 @@ impl OAuthParams for OwnerPasswordAccessTokenRequest {
     fn get_extra_params_ref(&self) -> Option<&Vec<(String, String)>> {
         self.extras.as_ref()
     }
+
+    fn get_client_auth(&self) -> Option<&ClientAuth> {
+        self.client_auth.as_ref()
+    }
 }
 
 impl OwnerPasswordAccessTokenRequest {
@@ -52,9 +59,14 @@ impl OwnerPasswordAccessTokenRequest {
             password,
             extras: None,
             scope: None,
+            client_auth: None,
         }
     }
 
+    pub fn set_client_auth(&mut self, client_auth: ClientAuth) {
+        self.client_auth = Some(client_auth);
+    }
+
     pub fn add_scope(&mut self, scope: String) {
         utils::add_scope(self, scope);
     }
@@ -75,11 +87,36 @@ impl OwnerPasswordAccessTokenRequest {
     }
 
     pub fn get_headers(&self) -> Vec<(String, String)> {
-        utils::get_headers()
+        utils::get_headers(self)
+    }
+
+    pub fn get_request_body(&self) -> Vec<u8> {
+        utils::get_request_body(self)
+    }
+
+    pub fn execute<C, T>(&self, client: &C, token_url: &str) -> Result<T, RequestError<C::Error>>
+    where
+        C: HttpClient,
+        T: Token + DeserializeOwned,
+    {
+        utils::execute_token_request(self, client, token_url)
+    }
+
+    pub async fn execute_async<C, T>(
+        &self,
+        client: &C,
+        token_url: &str,
+    ) -> Result<T, RequestError<C::Error>>
+    where
+        C: AsyncHttpClient,
+        T: Token + DeserializeOwned,
+    {
+        utils::execute_token_request_async(self, client, token_url).await
     }
 }
 
 #[cfg(test)]
+#[allow(clippy::bool_assert_comparison, clippy::useless_conversion)]
 mod owner_password_token_request_tests {
     use super::*;
     #[test]
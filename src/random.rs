@@ -0,0 +1,33 @@
+use rand::{CryptoRng, RngCore};
+
+pub(crate) struct Random<'a, R> {
+    rng: &'a mut R,
+}
+
+impl<'a, R> Random<'a, R>
+where
+    R: RngCore + CryptoRng,
+{
+    pub(crate) fn new(rng: &'a mut R) -> Self {
+        Random { rng }
+    }
+
+    pub(crate) fn get_octet_sequence(&mut self, len: usize) -> Vec<u8> {
+        let mut octets = vec![0u8; len];
+        self.rng.fill_bytes(&mut octets);
+        octets
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get_octect_sequence() {
+        let mut rng = rand::thread_rng();
+        let mut unreserved = Random::new(&mut rng);
+        let res = unreserved.get_octet_sequence(43);
+        assert_eq!(res.len(), 43);
+    }
+}
@@ -1,10 +1,13 @@
+use crate::http::{AsyncHttpClient, HttpClient, RequestError};
 use crate::utils;
-use crate::OAuthParams;
+use crate::{ClientAuth, OAuthParams, Token};
+use serde::de::DeserializeOwned;
 
 pub struct RefreshTokenRequest {
     refresh_token: String,
     extras: Option<Vec<(String, String)>>,
     scope: Option<Vec<String>>,
+    client_auth: Option<ClientAuth>,
 }
 
 impl OAuthParams for RefreshTokenRequest {
@@ -37,6 +40,10 @@ impl OAuthParams for RefreshTokenRequest {
     fn get_extra_params_ref(&self) -> Option<&Vec<(String, String)>> {
         self.extras.as_ref()
     }
+
+    fn get_client_auth(&self) -> Option<&ClientAuth> {
+        self.client_auth.as_ref()
+    }
 }
 
 impl RefreshTokenRequest {
@@ -45,9 +52,14 @@ impl RefreshTokenRequest {
             refresh_token,
             extras: None,
             scope: None,
+            client_auth: None,
         }
     }
 
+    pub fn set_client_auth(&mut self, client_auth: ClientAuth) {
+        self.client_auth = Some(client_auth);
+    }
+
     pub fn add_scope(&mut self, scope: String) {
         utils::add_scope(self, scope);
     }
@@ -68,6 +80,49 @@ impl RefreshTokenRequest {
     }
 
     pub fn get_headers(&self) -> Vec<(String, String)> {
-        utils::get_headers()
+        utils::get_headers(self)
+    }
+
+    pub fn get_request_body(&self) -> Vec<u8> {
+        utils::get_request_body(self)
+    }
+
+    pub fn execute<C, T>(&self, client: &C, token_url: &str) -> Result<T, RequestError<C::Error>>
+    where
+        C: HttpClient,
+        T: Token + DeserializeOwned,
+    {
+        utils::execute_token_request(self, client, token_url)
+    }
+
+    pub async fn execute_async<C, T>(
+        &self,
+        client: &C,
+        token_url: &str,
+    ) -> Result<T, RequestError<C::Error>>
+    where
+        C: AsyncHttpClient,
+        T: Token + DeserializeOwned,
+    {
+        utils::execute_token_request_async(self, client, token_url).await
+    }
+}
+
+#[cfg(test)]
+mod refresh_token_request_tests {
+    use super::*;
+    use crate::decode_form_params;
+
+    #[test]
+    fn test_get_request_body_round_trip() {
+        let mut request = RefreshTokenRequest::new("tGzv3JOkF0XG5Qx2TlKWIA".into());
+        request.add_scopes(["read".to_owned(), "write:all".to_owned()]);
+        request.add_extra_param("resource".into(), "https://api.example.com/ü".into());
+
+        let body = request.get_request_body();
+        assert_eq!(
+            decode_form_params(&body).unwrap(),
+            request.get_request_params_as_vec()
+        );
     }
 }
@@ -0,0 +1,265 @@
+use crate::{ClientAuth, OAuthParams, ServerMetadata};
+use std::error::Error;
+use std::fmt::Display;
+
+// A parameter of a request which the server metadata says is not
+// supported. Each variant holds the offending value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UnsupportedCapability {
+    ResponseType(String),
+    GrantType(String),
+    Scope(String),
+    CodeChallengeMethod(String),
+    ClientAuthMethod(String),
+}
+
+impl Display for UnsupportedCapability {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        use UnsupportedCapability::*;
+        match self {
+            ResponseType(v) => write!(f, "UnsupportedCapability::ResponseType({v})"),
+            GrantType(v) => write!(f, "UnsupportedCapability::GrantType({v})"),
+            Scope(v) => write!(f, "UnsupportedCapability::Scope({v})"),
+            CodeChallengeMethod(v) => {
+                write!(f, "UnsupportedCapability::CodeChallengeMethod({v})")
+            }
+            ClientAuthMethod(v) => write!(f, "UnsupportedCapability::ClientAuthMethod({v})"),
+        }
+    }
+}
+
+impl Error for UnsupportedCapability {}
+
+impl ServerMetadata {
+    // Checks an authorization or token request against what the server
+    // advertises. Members the server does not publish, like
+    // `scopes_supported`, are not checked. Client authentication is checked
+    // against `token_endpoint_auth_methods_supported`, except for assertion
+    // types which have no registered method name, like SAML 2.0 assertions.
+    pub fn validate_request<R>(&self, oauth_req: &R) -> Result<(), Vec<UnsupportedCapability>>
+    where
+        R: OAuthParams,
+    {
+        self.validate(oauth_req, &self.token_endpoint_auth_methods_supported())
+    }
+
+    pub fn validate_revocation_request<R>(
+        &self,
+        oauth_req: &R,
+    ) -> Result<(), Vec<UnsupportedCapability>>
+    where
+        R: OAuthParams,
+    {
+        self.validate(
+            oauth_req,
+            &self.revocation_endpoint_auth_methods_supported(),
+        )
+    }
+
+    pub fn validate_introspection_request<R>(
+        &self,
+        oauth_req: &R,
+    ) -> Result<(), Vec<UnsupportedCapability>>
+    where
+        R: OAuthParams,
+    {
+        self.validate(
+            oauth_req,
+            &self.introspection_endpoint_auth_methods_supported(),
+        )
+    }
+
+    fn validate<R>(
+        &self,
+        oauth_req: &R,
+        auth_methods: &[String],
+    ) -> Result<(), Vec<UnsupportedCapability>>
+    where
+        R: OAuthParams,
+    {
+        let mut unsupported = vec![];
+        if let Some(response_type) = oauth_req.get_response_type() {
+            if !self.supports_response_type(&response_type) {
+                unsupported.push(UnsupportedCapability::ResponseType(response_type));
+            }
+        }
+        if let Some(grant_type) = oauth_req.get_grant_type() {
+            if !self.supports_grant_type(&grant_type) {
+                unsupported.push(UnsupportedCapability::GrantType(grant_type));
+            }
+        }
+        if let (Some(scopes), Some(supported)) =
+            (oauth_req.get_scopes_ref(), self.scopes_supported())
+        {
+            unsupported.extend(
+                scopes
+                    .iter()
+                    .flat_map(|s| s.split_whitespace())
+                    .filter(|s| !supported.iter().any(|supported| supported == s))
+                    .map(|s| UnsupportedCapability::Scope(s.to_owned())),
+            );
+        }
+        if let Some(method) = oauth_req.get_code_challenge_method() {
+            let supported = self.code_challenge_methods_supported().unwrap_or_default();
+            if !supported.contains(&method) {
+                unsupported.push(UnsupportedCapability::CodeChallengeMethod(method));
+            }
+        }
+        if let Some(client_auth) = oauth_req.get_client_auth() {
+            let method = client_auth.get_auth_method();
+            // such assertions report their assertion type as the method,
+            // which servers cannot list in their metadata.
+            let unregistered = matches!(
+                &client_auth,
+                ClientAuth::Assertion { assertion, .. } if assertion.get_assertion_type() == method
+            );
+            if !unregistered && !auth_methods.contains(&method) {
+                unsupported.push(UnsupportedCapability::ClientAuthMethod(method));
+            }
+        }
+        if unsupported.is_empty() {
+            Ok(())
+        } else {
+            Err(unsupported)
+        }
+    }
+}
+
+#[cfg(test)]
+mod request_validation_tests {
+    use super::*;
+    use crate::{
+        AuthCodeRequest, ChallengMethod, ClientCredentialsGrantAuthTokenRequest, CodeVerifier,
+        RevocationRequest, Saml2ClientAssertion, StaticClientAssertion,
+    };
+
+    const METADATA: &str = r#"{
+        "issuer": "https://server.example.com",
+        "response_types_supported": ["code"],
+        "grant_types_supported": ["authorization_code", "refresh_token"],
+        "scopes_supported": ["openid", "profile", "email"],
+        "code_challenge_methods_supported": ["S256"],
+        "token_endpoint_auth_methods_supported": ["client_secret_basic", "private_key_jwt"],
+        "revocation_endpoint_auth_methods_supported": ["client_secret_post"]
+    }"#;
+
+    fn metadata() -> ServerMetadata {
+        ServerMetadata::from_json(METADATA.as_bytes()).unwrap()
+    }
+
+    #[test]
+    fn test_valid_auth_code_request() {
+        let mut request = AuthCodeRequest::new("s6BhdRkqt3".into(), "code".into());
+        request.add_scope("openid".into());
+        request.add_scope("email".into());
+        request.set_code_challenge(&CodeVerifier::new(), ChallengMethod::Sha256);
+        assert_eq!(metadata().validate_request(&request), Ok(()));
+    }
+
+    #[test]
+    fn test_invalid_auth_code_request() {
+        let mut request = AuthCodeRequest::new("s6BhdRkqt3".into(), "code".into());
+        request.add_scope("openid".into());
+        request.add_scope("phone".into());
+        request.set_code_challenge(&CodeVerifier::new(), ChallengMethod::Plain);
+        assert_eq!(
+            metadata().validate_request(&request),
+            Err(vec![
+                UnsupportedCapability::Scope("phone".into()),
+                UnsupportedCapability::CodeChallengeMethod("plain".into()),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_unsupported_response_type() {
+        let request = AuthCodeRequest::new("s6BhdRkqt3".into(), "token".into());
+        assert_eq!(
+            metadata().validate_request(&request),
+            Err(vec![UnsupportedCapability::ResponseType("token".into())])
+        );
+    }
+
+    #[test]
+    fn test_invalid_token_request() {
+        let mut request = ClientCredentialsGrantAuthTokenRequest::new();
+        request.set_client_auth(ClientAuth::ClientSecretPost {
+            client_id: "s6BhdRkqt3".into(),
+            client_secret: "7Fjfp0ZBr1KtDRbnfVdmIw".into(),
+        });
+        assert_eq!(
+            metadata().validate_request(&request),
+            Err(vec![
+                UnsupportedCapability::GrantType("client_credentials".into()),
+                UnsupportedCapability::ClientAuthMethod("client_secret_post".into()),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_revocation_request_auth_method() {
+        let mut request = RevocationRequest::new("45ghiukldjahdnhzdauz".into());
+        request.set_client_auth(ClientAuth::ClientSecretPost {
+            client_id: "s6BhdRkqt3".into(),
+            client_secret: "7Fjfp0ZBr1KtDRbnfVdmIw".into(),
+        });
+        assert_eq!(metadata().validate_revocation_request(&request), Ok(()));
+        // introspection falls back to client_secret_basic when omitted.
+        assert_eq!(
+            metadata().validate_introspection_request(&request),
+            Err(vec![UnsupportedCapability::ClientAuthMethod(
+                "client_secret_post".into()
+            )])
+        );
+    }
+
+    #[test]
+    fn test_unregistered_assertion_auth_method() {
+        let mut request = ClientCredentialsGrantAuthTokenRequest::new();
+        request
+            .set_client_auth(Saml2ClientAssertion::new("<saml:Assertion/>").into_client_auth(None));
+        assert_eq!(
+            metadata().validate_request(&request),
+            Err(vec![UnsupportedCapability::GrantType(
+                "client_credentials".into()
+            )])
+        );
+
+        // registered names are still checked.
+        let assertion = StaticClientAssertion::new(
+            "client_secret_jwt".into(),
+            "urn:ietf:params:oauth:client-assertion-type:jwt-bearer".into(),
+            "eyJhbGciOiJIUzI1NiJ9.e30.sig".into(),
+        );
+        request.set_client_auth(ClientAuth::Assertion {
+            client_id: None,
+            assertion: std::sync::Arc::new(assertion),
+        });
+        assert_eq!(
+            metadata().validate_request(&request),
+            Err(vec![
+                UnsupportedCapability::GrantType("client_credentials".into()),
+                UnsupportedCapability::ClientAuthMethod("client_secret_jwt".into()),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_pkce_not_advertised() {
+        let metadata = ServerMetadata::from_json(
+            br#"{"issuer": "https://server.example.com", "response_types_supported": ["code"]}"#,
+        )
+        .unwrap();
+        let mut request = AuthCodeRequest::new("s6BhdRkqt3".into(), "code".into());
+        // scopes are not checked when the server does not list them.
+        request.add_scope("anything".into());
+        assert_eq!(metadata.validate_request(&request), Ok(()));
+        request.set_code_challenge(&CodeVerifier::new(), ChallengMethod::Sha256);
+        assert_eq!(
+            metadata.validate_request(&request),
+            Err(vec![UnsupportedCapability::CodeChallengeMethod(
+                "S256".into()
+            )])
+        );
+    }
+}
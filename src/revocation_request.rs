@@ -0,0 +1,220 @@
+use crate::http::{AsyncHttpClient, HttpClient, RequestError};
+use crate::utils;
+use crate::{ClientAuth, OAuthParams, Token};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TokenTypeHint {
+    AccessToken,
+    RefreshToken,
+    Other(String),
+}
+
+impl TokenTypeHint {
+    pub fn as_str(&self) -> &str {
+        match self {
+            TokenTypeHint::AccessToken => "access_token",
+            TokenTypeHint::RefreshToken => "refresh_token",
+            TokenTypeHint::Other(s) => s,
+        }
+    }
+}
+
+// Token Revocation Request (RFC 7009 section 2.1).
+#[derive(Debug)]
+pub struct RevocationRequest {
+    token: String,
+    token_type_hint: Option<TokenTypeHint>,
+    extras: Option<Vec<(String, String)>>,
+    client_auth: Option<ClientAuth>,
+}
+
+impl OAuthParams for RevocationRequest {
+    fn get_extra_params_mut(&mut self) -> Option<&mut Vec<(String, String)>> {
+        if self.extras.is_none() {
+            self.extras = Some(vec![])
+        }
+        self.extras.as_mut()
+    }
+
+    fn get_extra_params_ref(&self) -> Option<&Vec<(String, String)>> {
+        self.extras.as_ref()
+    }
+
+    fn get_client_auth(&self) -> Option<&ClientAuth> {
+        self.client_auth.as_ref()
+    }
+
+    fn get_token(&self) -> Option<String> {
+        Some(self.token.clone())
+    }
+
+    fn get_token_type_hint(&self) -> Option<String> {
+        self.token_type_hint.as_ref().map(|h| h.as_str().to_owned())
+    }
+}
+
+impl RevocationRequest {
+    pub fn new(token: String) -> Self {
+        Self {
+            token,
+            token_type_hint: None,
+            extras: None,
+            client_auth: None,
+        }
+    }
+
+    // Revokes the refresh token when there is one, since servers also
+    // invalidate the access tokens issued from it (RFC 7009 section 2.1).
+    // Falls back to the access token otherwise.
+    pub fn from_token<T>(token: &T) -> Self
+    where
+        T: Token,
+    {
+        match token.refresh_token() {
+            Some(refresh_token) => {
+                let mut request = Self::new(refresh_token);
+                request.set_token_type_hint(TokenTypeHint::RefreshToken);
+                request
+            }
+            None => Self::from_access_token(token),
+        }
+    }
+
+    pub fn from_access_token<T>(token: &T) -> Self
+    where
+        T: Token,
+    {
+        let mut request = Self::new(token.access_token());
+        request.set_token_type_hint(TokenTypeHint::AccessToken);
+        request
+    }
+
+    pub fn set_token_type_hint(&mut self, token_type_hint: TokenTypeHint) {
+        self.token_type_hint = Some(token_type_hint);
+    }
+
+    pub fn set_client_auth(&mut self, client_auth: ClientAuth) {
+        self.client_auth = Some(client_auth);
+    }
+
+    pub fn add_extra_param(&mut self, key: String, value: String) {
+        utils::add_extra_param(self, key, value);
+    }
+
+    pub fn get_request_params_as_vec(&self) -> Vec<(String, String)> {
+        utils::get_request_params_as_vec(self)
+    }
+
+    pub fn get_headers(&self) -> Vec<(String, String)> {
+        utils::get_headers(self)
+    }
+
+    pub fn get_request_body(&self) -> Vec<u8> {
+        utils::get_request_body(self)
+    }
+
+    // The server answers 200 whether or not the token was valid, usually
+    // with an empty body, so success carries no value.
+    pub fn execute<C>(&self, client: &C, revocation_url: &str) -> Result<(), RequestError<C::Error>>
+    where
+        C: HttpClient,
+    {
+        utils::execute_request_without_content(self, client, revocation_url)
+    }
+
+    pub async fn execute_async<C>(
+        &self,
+        client: &C,
+        revocation_url: &str,
+    ) -> Result<(), RequestError<C::Error>>
+    where
+        C: AsyncHttpClient,
+    {
+        utils::execute_request_without_content_async(self, client, revocation_url).await
+    }
+}
+
+#[cfg(test)]
+mod revocation_request_tests {
+    use super::*;
+    use crate::{AuthTokenErrorKind, HttpResponse, MockHttpClient, TokenResponse};
+
+    #[test]
+    fn test_from_token_prefers_refresh_token() {
+        let mut token = TokenResponse::new("access".into(), "Bearer".into());
+        token.set_refresh_token("refresh".into());
+
+        let request = RevocationRequest::from_token(&token);
+        assert_eq!(
+            request.get_request_params_as_vec(),
+            vec![
+                ("token".to_owned(), "refresh".to_owned()),
+                ("token_type_hint".to_owned(), "refresh_token".to_owned()),
+            ]
+        );
+
+        let request = RevocationRequest::from_access_token(&token);
+        assert_eq!(
+            request.get_request_params_as_vec(),
+            vec![
+                ("token".to_owned(), "access".to_owned()),
+                ("token_type_hint".to_owned(), "access_token".to_owned()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_from_token_without_refresh_token() {
+        let token = TokenResponse::new("access".into(), "Bearer".into());
+        let mut request = RevocationRequest::from_token(&token);
+        request.set_client_auth(ClientAuth::ClientSecretBasic {
+            client_id: "s6BhdRkqt3".into(),
+            client_secret: "gX1fBat3bV".into(),
+        });
+        assert_eq!(
+            String::from_utf8(request.get_request_body()).unwrap(),
+            "token=access&token_type_hint=access_token"
+        );
+        assert_eq!(request.get_headers().len(), 2);
+    }
+
+    #[test]
+    fn test_execute_empty_body() {
+        let client = MockHttpClient::new();
+        client.push_response(HttpResponse::new(200, vec![]));
+        let request = RevocationRequest::new("45ghiukldjahdnhzdauz".into());
+        assert!(request
+            .execute(&client, "https://server.example.com/revoke")
+            .is_ok());
+    }
+
+    #[test]
+    fn test_execute_unsupported_token_type() {
+        let client = MockHttpClient::new();
+        client.push_response(HttpResponse::new(
+            400,
+            r#"{"error": "unsupported_token_type"}"#.into(),
+        ));
+        let request = RevocationRequest::new("45ghiukldjahdnhzdauz".into());
+        match request.execute(&client, "https://server.example.com/revoke") {
+            Err(RequestError::ServerResponse(e)) => {
+                assert!(matches!(
+                    e.error(),
+                    AuthTokenErrorKind::UnsupportedTokenType
+                ))
+            }
+            res => panic!("unexpected result: {res:?}"),
+        }
+    }
+
+    #[test]
+    fn test_execute_service_unavailable() {
+        let client = MockHttpClient::new();
+        client.push_response(HttpResponse::new(503, vec![]));
+        let request = RevocationRequest::new("45ghiukldjahdnhzdauz".into());
+        assert!(matches!(
+            request.execute(&client, "https://server.example.com/revoke"),
+            Err(RequestError::UnexpectedStatus(503, _))
+        ));
+    }
+}
@@ -0,0 +1,211 @@
+use crate::http::{AsyncHttpClient, HttpClient, RequestError};
+use crate::utils;
+use crate::{ClientAssertion, ClientAssertionError, ClientAuth, OAuthParams, Token};
+use serde::de::DeserializeOwned;
+use std::sync::Arc;
+
+pub const SAML2_BEARER_GRANT_TYPE: &str = "urn:ietf:params:oauth:grant-type:saml2-bearer";
+pub const SAML2_BEARER_CLIENT_ASSERTION_TYPE: &str =
+    "urn:ietf:params:oauth:client-assertion-type:saml2-bearer";
+
+// RFC 7522 section 2.1: the assertion is base64url encoded without line
+// wrapping and without padding.
+fn encode_assertion(assertion_xml: &str) -> String {
+    data_encoding::BASE64URL_NOPAD.encode(assertion_xml.as_bytes())
+}
+
+// SAML 2.0 assertion used for client authentication (RFC 7522 section 2.2).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Saml2ClientAssertion {
+    assertion: String,
+}
+
+impl Saml2ClientAssertion {
+    pub fn new(assertion_xml: &str) -> Self {
+        Self {
+            assertion: encode_assertion(assertion_xml),
+        }
+    }
+
+    pub fn into_client_auth(self, client_id: Option<String>) -> ClientAuth {
+        ClientAuth::Assertion {
+            client_id,
+            assertion: Arc::new(self),
+        }
+    }
+}
+
+impl ClientAssertion for Saml2ClientAssertion {
+    // RFC 7522 does not register a token_endpoint_auth_method name, so the
+    // assertion type is used to identify the method.
+    fn get_auth_method(&self) -> String {
+        SAML2_BEARER_CLIENT_ASSERTION_TYPE.into()
+    }
+
+    fn get_assertion_type(&self) -> String {
+        SAML2_BEARER_CLIENT_ASSERTION_TYPE.into()
+    }
+
+    fn get_assertion(&self) -> Result<String, ClientAssertionError> {
+        Ok(self.assertion.clone())
+    }
+}
+
+// SAML 2.0 Bearer Assertion authorization grant (RFC 7522 section 2.1).
+#[derive(Debug)]
+pub struct Saml2BearerTokenRequest {
+    assertion: String,
+    scope: Option<Vec<String>>,
+    extras: Option<Vec<(String, String)>>,
+    client_auth: Option<ClientAuth>,
+}
+
+impl OAuthParams for Saml2BearerTokenRequest {
+    fn get_grant_type(&self) -> Option<String> {
+        SAML2_BEARER_GRANT_TYPE.to_owned().into()
+    }
+
+    fn get_assertion(&self) -> Option<String> {
+        Some(self.assertion.clone())
+    }
+
+    fn get_scopes_mut(&mut self) -> Option<&mut Vec<String>> {
+        if self.scope.is_none() {
+            self.scope = Some(vec![])
+        }
+        self.scope.as_mut()
+    }
+
+    fn get_scopes_ref(&self) -> Option<&Vec<String>> {
+        self.scope.as_ref()
+    }
+
+    fn get_extra_params_mut(&mut self) -> Option<&mut Vec<(String, String)>> {
+        if self.extras.is_none() {
+            self.extras = Some(vec![])
+        }
+        self.extras.as_mut()
+    }
+
+    fn get_extra_params_ref(&self) -> Option<&Vec<(String, String)>> {
+        self.extras.as_ref()
+    }
+
+    fn get_client_auth(&self) -> Option<&ClientAuth> {
+        self.client_auth.as_ref()
+    }
+}
+
+impl Saml2BearerTokenRequest {
+    pub fn new(assertion_xml: &str) -> Self {
+        Self::from_encoded_assertion(encode_assertion(assertion_xml))
+    }
+
+    // for assertions which are already base64url encoded, like the ones
+    // handed out by some identity providers.
+    pub fn from_encoded_assertion(assertion: String) -> Self {
+        Self {
+            assertion,
+            scope: None,
+            extras: None,
+            client_auth: None,
+        }
+    }
+
+    pub fn set_client_auth(&mut self, client_auth: ClientAuth) {
+        self.client_auth = Some(client_auth);
+    }
+
+    pub fn add_scope(&mut self, scope: String) {
+        utils::add_scope(self, scope);
+    }
+
+    pub fn add_scopes<I>(&mut self, scopes: I)
+    where
+        I: IntoIterator<Item = String>,
+    {
+        utils::add_scopes(self, scopes);
+    }
+
+    pub fn add_extra_param(&mut self, key: String, value: String) {
+        utils::add_extra_param(self, key, value);
+    }
+
+    pub fn get_request_params_as_vec(&self) -> Vec<(String, String)> {
+        utils::get_request_params_as_vec(self)
+    }
+
+    pub fn get_headers(&self) -> Vec<(String, String)> {
+        utils::get_headers(self)
+    }
+
+    pub fn get_request_body(&self) -> Vec<u8> {
+        utils::get_request_body(self)
+    }
+
+    pub fn execute<C, T>(&self, client: &C, token_url: &str) -> Result<T, RequestError<C::Error>>
+    where
+        C: HttpClient,
+        T: Token + DeserializeOwned,
+    {
+        utils::execute_token_request(self, client, token_url)
+    }
+
+    pub async fn execute_async<C, T>(
+        &self,
+        client: &C,
+        token_url: &str,
+    ) -> Result<T, RequestError<C::Error>>
+    where
+        C: AsyncHttpClient,
+        T: Token + DeserializeOwned,
+    {
+        utils::execute_token_request_async(self, client, token_url).await
+    }
+}
+
+#[cfg(test)]
+mod saml2_bearer_token_request_tests {
+    use super::*;
+
+    // bytes chosen so that standard base64 would produce '+', '/' and '='.
+    const ASSERTION: &str = "<saml:Assertion>\u{fb}\u{ff}?</saml:Assertion>";
+
+    #[test]
+    fn test_assertion_is_base64url_without_padding() {
+        let request = Saml2BearerTokenRequest::new(ASSERTION);
+        let params = request.get_request_params_as_vec();
+        let (_, assertion) = params.iter().find(|(k, _)| k == "assertion").unwrap();
+        assert!(!assertion.contains(['+', '/', '=', '\n']));
+        assert_eq!(
+            data_encoding::BASE64URL_NOPAD
+                .decode(assertion.as_bytes())
+                .unwrap(),
+            ASSERTION.as_bytes()
+        );
+        assert!(params.contains(&("grant_type".into(), SAML2_BEARER_GRANT_TYPE.into())));
+    }
+
+    #[test]
+    fn test_client_auth() {
+        let mut request = Saml2BearerTokenRequest::from_encoded_assertion("PHNhbWw-".into());
+        request.set_client_auth(
+            Saml2ClientAssertion::new(ASSERTION).into_client_auth(Some("s6BhdRkqt3".into())),
+        );
+        let params = request.get_request_params_as_vec();
+        assert!(params.contains(&("assertion".into(), "PHNhbWw-".into())));
+        assert!(params.contains(&("client_id".into(), "s6BhdRkqt3".into())));
+        assert!(params.contains(&(
+            "client_assertion_type".into(),
+            SAML2_BEARER_CLIENT_ASSERTION_TYPE.into()
+        )));
+        assert_eq!(
+            request
+                .get_client_auth()
+                .unwrap()
+                .get_client_assertion()
+                .unwrap(),
+            Some(encode_assertion(ASSERTION))
+        );
+    }
+}
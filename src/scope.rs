@@ -0,0 +1,67 @@
+use serde::{self, Deserializer};
+use std::fmt;
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub(crate) enum Scope {
+    Array(Vec<String>),
+    Str(String),
+}
+
+struct MyVisitor;
+
+impl<'de> serde::de::Visitor<'de> for MyVisitor {
+    type Value = Scope;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        write!(f, "Failed to parse scopes")?;
+        Ok(())
+    }
+
+    fn visit_seq<A>(self, mut value: A) -> Result<Self::Value, A::Error>
+    where
+        A: serde::de::SeqAccess<'de>,
+    {
+        let mut vector: Vec<String> = Vec::with_capacity(value.size_hint().unwrap_or(0));
+        loop {
+            let element = value.next_element()?;
+            match element {
+                Some(data) => vector.push(data),
+                None => break,
+            }
+        }
+        Ok(Scope::Array(vector))
+    }
+
+    fn visit_str<E>(self, value: &str) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        Ok(Scope::Str(String::from(value)))
+    }
+
+    fn visit_string<E>(self, v: String) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        Ok(Scope::Str(v))
+    }
+}
+
+impl<'de> serde::de::Deserialize<'de> for Scope {
+    fn deserialize<D>(d: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let visitor = MyVisitor {};
+        d.deserialize_any(visitor)
+    }
+}
+
+impl Scope {
+    pub(crate) fn to_space_separated(&self) -> String {
+        match self {
+            Scope::Array(ref arr) => arr.join(" "),
+            Scope::Str(ref s) => s.clone(),
+        }
+    }
+}
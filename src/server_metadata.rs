@@ -0,0 +1,539 @@
+use crate::http::{self, AsyncHttpClient, HttpClient, RequestError};
+use crate::ChallengMethod;
+use serde::{self, Deserialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt::Display;
+
+const OAUTH_AUTHORIZATION_SERVER: &str = "oauth-authorization-server";
+const OPENID_CONFIGURATION: &str = "openid-configuration";
+
+// Where the metadata document of an issuer is published.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WellKnown {
+    // RFC 8414 section 3.1: the well-known path is inserted between the
+    // host and the path of the issuer.
+    OAuthAuthorizationServer,
+    // OpenID Connect Discovery 1.0 section 4: the well-known path is
+    // appended to the issuer.
+    OpenIdConfiguration,
+}
+
+impl WellKnown {
+    pub fn url(&self, issuer: &str) -> Result<String, MetadataError> {
+        let (authority, path) = split_issuer(issuer)?;
+        // a terminating "/" must be removed in both cases.
+        let path = path.trim_end_matches('/');
+        let url = match self {
+            WellKnown::OAuthAuthorizationServer => format!(
+                "https://{}/.well-known/{}{}",
+                authority, OAUTH_AUTHORIZATION_SERVER, path
+            ),
+            WellKnown::OpenIdConfiguration => format!(
+                "https://{}{}/.well-known/{}",
+                authority, path, OPENID_CONFIGURATION
+            ),
+        };
+        Ok(url)
+    }
+}
+
+// The issuer identifier is a https URL with no query or fragment
+// (RFC 8414 section 2). Returns the authority and the path.
+fn split_issuer(issuer: &str) -> Result<(&str, &str), MetadataError> {
+    let invalid = || MetadataError::InvalidIssuer(issuer.to_owned());
+    let rest = issuer.strip_prefix("https://").ok_or_else(invalid)?;
+    if rest.contains(['?', '#']) {
+        return Err(invalid());
+    }
+    let (authority, path) = match rest.find('/') {
+        Some(i) => rest.split_at(i),
+        None => (rest, ""),
+    };
+    if authority.is_empty() {
+        return Err(invalid());
+    }
+    Ok((authority, path))
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MetadataError {
+    InvalidIssuer(String),
+    // the `issuer` in the document is not identical to the one used to
+    // build the well-known URL (RFC 8414 section 3.3).
+    IssuerMismatch { expected: String, found: String },
+}
+
+impl Display for MetadataError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        use MetadataError::*;
+        match self {
+            InvalidIssuer(issuer) => write!(f, "MetadataError::InvalidIssuer({issuer})"),
+            IssuerMismatch { expected, found } => write!(
+                f,
+                "MetadataError::IssuerMismatch(expected = {expected}, found = {found})"
+            ),
+        }
+    }
+}
+
+impl Error for MetadataError {}
+
+#[derive(Debug)]
+pub enum DiscoveryError<E> {
+    Metadata(MetadataError),
+    Request(RequestError<E>),
+}
+
+impl<E> Display for DiscoveryError<E>
+where
+    E: Display,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DiscoveryError::Metadata(e) => write!(f, "DiscoveryError::Metadata({e})"),
+            DiscoveryError::Request(e) => write!(f, "DiscoveryError::Request({e})"),
+        }
+    }
+}
+
+impl<E> Error for DiscoveryError<E>
+where
+    E: Error + 'static,
+{
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            DiscoveryError::Metadata(e) => Some(e),
+            DiscoveryError::Request(e) => Some(e),
+        }
+    }
+}
+
+// Authorization server metadata (RFC 8414 section 2), which is a superset
+// of the OpenID Provider metadata for the members we care about. Anything
+// else is kept in `extras`.
+#[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
+pub struct ServerMetadata {
+    issuer: String,
+    authorization_endpoint: Option<String>,
+    token_endpoint: Option<String>,
+    jwks_uri: Option<String>,
+    registration_endpoint: Option<String>,
+    userinfo_endpoint: Option<String>,
+    revocation_endpoint: Option<String>,
+    introspection_endpoint: Option<String>,
+    device_authorization_endpoint: Option<String>,
+    scopes_supported: Option<Vec<String>>,
+    response_types_supported: Vec<String>,
+    response_modes_supported: Option<Vec<String>>,
+    grant_types_supported: Option<Vec<String>>,
+    token_endpoint_auth_methods_supported: Option<Vec<String>>,
+    token_endpoint_auth_signing_alg_values_supported: Option<Vec<String>>,
+    revocation_endpoint_auth_methods_supported: Option<Vec<String>>,
+    introspection_endpoint_auth_methods_supported: Option<Vec<String>>,
+    code_challenge_methods_supported: Option<Vec<String>>,
+    subject_types_supported: Option<Vec<String>>,
+    id_token_signing_alg_values_supported: Option<Vec<String>>,
+    claims_supported: Option<Vec<String>>,
+    #[serde(flatten)]
+    extras: HashMap<String, Value>,
+}
+
+// Defaults which apply when the member is omitted (RFC 8414 section 2).
+const DEFAULT_GRANT_TYPES: &[&str] = &["authorization_code", "implicit"];
+const DEFAULT_RESPONSE_MODES: &[&str] = &["query", "fragment"];
+const DEFAULT_AUTH_METHODS: &[&str] = &["client_secret_basic"];
+
+fn with_default(values: &Option<Vec<String>>, default: &[&str]) -> Vec<String> {
+    match values {
+        Some(values) => values.clone(),
+        None => default.iter().map(|v| (*v).to_owned()).collect(),
+    }
+}
+
+impl ServerMetadata {
+    pub fn from_json(json: &[u8]) -> Result<Self, serde_json::Error> {
+        serde_json::from_slice(json)
+    }
+
+    pub fn validate_issuer(&self, expected_issuer: &str) -> Result<(), MetadataError> {
+        if self.issuer != expected_issuer {
+            return Err(MetadataError::IssuerMismatch {
+                expected: expected_issuer.to_owned(),
+                found: self.issuer.clone(),
+            });
+        }
+        Ok(())
+    }
+
+    pub fn discover<C>(
+        client: &C,
+        issuer: &str,
+        well_known: WellKnown,
+    ) -> Result<Self, DiscoveryError<C::Error>>
+    where
+        C: HttpClient,
+    {
+        let url = well_known.url(issuer).map_err(DiscoveryError::Metadata)?;
+        let response = client
+            .execute(http::get_json(&url))
+            .map_err(|e| DiscoveryError::Request(RequestError::Transport(e)))?;
+        Self::from_discovery_response(response, issuer)
+    }
+
+    pub async fn discover_async<C>(
+        client: &C,
+        issuer: &str,
+        well_known: WellKnown,
+    ) -> Result<Self, DiscoveryError<C::Error>>
+    where
+        C: AsyncHttpClient,
+    {
+        let url = well_known.url(issuer).map_err(DiscoveryError::Metadata)?;
+        let response = client
+            .execute(http::get_json(&url))
+            .await
+            .map_err(|e| DiscoveryError::Request(RequestError::Transport(e)))?;
+        Self::from_discovery_response(response, issuer)
+    }
+
+    fn from_discovery_response<E>(
+        response: http::HttpResponse,
+        issuer: &str,
+    ) -> Result<Self, DiscoveryError<E>> {
+        let metadata: Self =
+            http::parse_json_response(response).map_err(DiscoveryError::Request)?;
+        metadata
+            .validate_issuer(issuer)
+            .map_err(DiscoveryError::Metadata)?;
+        Ok(metadata)
+    }
+
+    pub fn issuer(&self) -> String {
+        self.issuer.clone()
+    }
+
+    pub fn authorization_endpoint(&self) -> Option<String> {
+        self.authorization_endpoint.clone()
+    }
+
+    pub fn token_endpoint(&self) -> Option<String> {
+        self.token_endpoint.clone()
+    }
+
+    pub fn jwks_uri(&self) -> Option<String> {
+        self.jwks_uri.clone()
+    }
+
+    pub fn registration_endpoint(&self) -> Option<String> {
+        self.registration_endpoint.clone()
+    }
+
+    pub fn userinfo_endpoint(&self) -> Option<String> {
+        self.userinfo_endpoint.clone()
+    }
+
+    pub fn revocation_endpoint(&self) -> Option<String> {
+        self.revocation_endpoint.clone()
+    }
+
+    pub fn introspection_endpoint(&self) -> Option<String> {
+        self.introspection_endpoint.clone()
+    }
+
+    pub fn device_authorization_endpoint(&self) -> Option<String> {
+        self.device_authorization_endpoint.clone()
+    }
+
+    // None when the server does not advertise its scopes, which does not
+    // mean that it supports none.
+    pub fn scopes_supported(&self) -> Option<Vec<String>> {
+        self.scopes_supported.clone()
+    }
+
+    pub fn response_types_supported(&self) -> Vec<String> {
+        self.response_types_supported.clone()
+    }
+
+    pub fn response_modes_supported(&self) -> Vec<String> {
+        with_default(&self.response_modes_supported, DEFAULT_RESPONSE_MODES)
+    }
+
+    pub fn grant_types_supported(&self) -> Vec<String> {
+        with_default(&self.grant_types_supported, DEFAULT_GRANT_TYPES)
+    }
+
+    pub fn token_endpoint_auth_methods_supported(&self) -> Vec<String> {
+        with_default(
+            &self.token_endpoint_auth_methods_supported,
+            DEFAULT_AUTH_METHODS,
+        )
+    }
+
+    pub fn token_endpoint_auth_signing_alg_values_supported(&self) -> Option<Vec<String>> {
+        self.token_endpoint_auth_signing_alg_values_supported
+            .clone()
+    }
+
+    pub fn revocation_endpoint_auth_methods_supported(&self) -> Vec<String> {
+        with_default(
+            &self.revocation_endpoint_auth_methods_supported,
+            DEFAULT_AUTH_METHODS,
+        )
+    }
+
+    pub fn introspection_endpoint_auth_methods_supported(&self) -> Vec<String> {
+        with_default(
+            &self.introspection_endpoint_auth_methods_supported,
+            DEFAULT_AUTH_METHODS,
+        )
+    }
+
+    // None when omitted, in which case the server does not support PKCE
+    // as far as the client can tell (RFC 8414 section 2).
+    pub fn code_challenge_methods_supported(&self) -> Option<Vec<String>> {
+        self.code_challenge_methods_supported.clone()
+    }
+
+    pub fn subject_types_supported(&self) -> Option<Vec<String>> {
+        self.subject_types_supported.clone()
+    }
+
+    pub fn id_token_signing_alg_values_supported(&self) -> Option<Vec<String>> {
+        self.id_token_signing_alg_values_supported.clone()
+    }
+
+    pub fn claims_supported(&self) -> Option<Vec<String>> {
+        self.claims_supported.clone()
+    }
+
+    pub fn supports_response_type(&self, response_type: &str) -> bool {
+        self.response_types_supported
+            .iter()
+            .any(|r| r == response_type)
+    }
+
+    pub fn supports_grant_type(&self, grant_type: &str) -> bool {
+        self.grant_types_supported().iter().any(|g| g == grant_type)
+    }
+
+    pub fn supports_token_endpoint_auth_method(&self, auth_method: &str) -> bool {
+        self.token_endpoint_auth_methods_supported()
+            .iter()
+            .any(|m| m == auth_method)
+    }
+
+    pub fn supports_code_challenge_method(&self, method: &ChallengMethod) -> bool {
+        self.code_challenge_methods_supported
+            .as_ref()
+            .is_some_and(|methods| methods.iter().any(|m| m == method.as_str()))
+    }
+
+    pub fn get_extra_field(&self, name: &str) -> Option<Value> {
+        self.extras.get(name).cloned()
+    }
+
+    pub fn get_extra_fields(&self) -> HashMap<String, Value> {
+        self.extras.clone()
+    }
+}
+
+#[cfg(test)]
+mod server_metadata_tests {
+    use super::*;
+    use crate::{HttpMethod, HttpResponse, MockHttpClient};
+    use serde_json::json;
+
+    // RFC 8414 section 3.2 example, with PKCE and an extension member.
+    const METADATA: &str = r#"{
+        "issuer": "https://server.example.com",
+        "authorization_endpoint": "https://server.example.com/authorize",
+        "token_endpoint": "https://server.example.com/token",
+        "token_endpoint_auth_methods_supported": ["client_secret_basic", "private_key_jwt"],
+        "token_endpoint_auth_signing_alg_values_supported": ["RS256", "ES256"],
+        "userinfo_endpoint": "https://server.example.com/userinfo",
+        "jwks_uri": "https://server.example.com/jwks.json",
+        "registration_endpoint": "https://server.example.com/register",
+        "scopes_supported": ["openid", "profile", "email", "address", "phone", "offline_access"],
+        "response_types_supported": ["code", "code token"],
+        "code_challenge_methods_supported": ["S256"],
+        "service_documentation": "http://server.example.com/service_documentation.html",
+        "ui_locales_supported": ["en-US", "en-GB", "en-CA", "fr-FR", "fr-CA"]
+    }"#;
+
+    #[test]
+    fn test_well_known_url() {
+        use WellKnown::*;
+        assert_eq!(
+            OAuthAuthorizationServer.url("https://example.com").unwrap(),
+            "https://example.com/.well-known/oauth-authorization-server"
+        );
+        assert_eq!(
+            OAuthAuthorizationServer
+                .url("https://example.com/issuer1")
+                .unwrap(),
+            "https://example.com/.well-known/oauth-authorization-server/issuer1"
+        );
+        assert_eq!(
+            OAuthAuthorizationServer
+                .url("https://example.com/tenant/1/")
+                .unwrap(),
+            "https://example.com/.well-known/oauth-authorization-server/tenant/1"
+        );
+        assert_eq!(
+            OpenIdConfiguration.url("https://example.com/").unwrap(),
+            "https://example.com/.well-known/openid-configuration"
+        );
+        assert_eq!(
+            OpenIdConfiguration
+                .url("https://example.com/issuer1/")
+                .unwrap(),
+            "https://example.com/issuer1/.well-known/openid-configuration"
+        );
+        for issuer in [
+            "http://example.com",
+            "https://",
+            "https://example.com?tenant=1",
+            "https://example.com/#x",
+        ] {
+            assert_eq!(
+                OpenIdConfiguration.url(issuer),
+                Err(MetadataError::InvalidIssuer(issuer.to_owned()))
+            );
+        }
+    }
+
+    #[test]
+    fn test_metadata_deserialization() {
+        let metadata = ServerMetadata::from_json(METADATA.as_bytes()).unwrap();
+        assert_eq!(metadata.issuer(), "https://server.example.com");
+        assert_eq!(
+            metadata.token_endpoint(),
+            Some("https://server.example.com/token".to_owned())
+        );
+        assert_eq!(metadata.revocation_endpoint(), None);
+        assert!(metadata.supports_response_type("code"));
+        assert!(!metadata.supports_response_type("token"));
+        assert!(metadata.supports_token_endpoint_auth_method("private_key_jwt"));
+        assert!(!metadata.supports_token_endpoint_auth_method("client_secret_post"));
+        assert!(metadata.supports_code_challenge_method(&ChallengMethod::Sha256));
+        assert!(!metadata.supports_code_challenge_method(&ChallengMethod::Plain));
+        assert_eq!(metadata.scopes_supported().map(|s| s.len()), Some(6));
+        assert_eq!(
+            metadata.get_extra_field("ui_locales_supported"),
+            Some(json!(["en-US", "en-GB", "en-CA", "fr-FR", "fr-CA"]))
+        );
+        assert_eq!(metadata.get_extra_fields().len(), 2);
+    }
+
+    #[test]
+    fn test_metadata_defaults() {
+        let metadata = ServerMetadata::from_json(
+            br#"{"issuer": "https://server.example.com", "response_types_supported": ["code"]}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            metadata.grant_types_supported(),
+            vec!["authorization_code", "implicit"]
+        );
+        assert_eq!(
+            metadata.response_modes_supported(),
+            vec!["query", "fragment"]
+        );
+        assert_eq!(
+            metadata.token_endpoint_auth_methods_supported(),
+            vec!["client_secret_basic"]
+        );
+        assert_eq!(
+            metadata.revocation_endpoint_auth_methods_supported(),
+            vec!["client_secret_basic"]
+        );
+        assert!(metadata.supports_grant_type("authorization_code"));
+        assert!(!metadata.supports_grant_type("client_credentials"));
+        assert_eq!(metadata.code_challenge_methods_supported(), None);
+        assert!(!metadata.supports_code_challenge_method(&ChallengMethod::Sha256));
+        assert_eq!(metadata.scopes_supported(), None);
+    }
+
+    #[test]
+    fn test_discover() {
+        let client = MockHttpClient::new();
+        client.push_response(HttpResponse::new(200, METADATA.into()));
+        let metadata = ServerMetadata::discover(
+            &client,
+            "https://server.example.com",
+            WellKnown::OAuthAuthorizationServer,
+        )
+        .unwrap();
+        assert_eq!(metadata.issuer(), "https://server.example.com");
+
+        let requests = client.get_requests();
+        assert_eq!(requests[0].method, HttpMethod::Get);
+        assert_eq!(
+            requests[0].url,
+            "https://server.example.com/.well-known/oauth-authorization-server"
+        );
+        assert!(requests[0].body.is_empty());
+    }
+
+    #[test]
+    fn test_discover_issuer_mismatch() {
+        let client = MockHttpClient::new();
+        client.push_response(HttpResponse::new(200, METADATA.into()));
+        // a trailing "/" makes it a different issuer.
+        match ServerMetadata::discover(
+            &client,
+            "https://server.example.com/",
+            WellKnown::OpenIdConfiguration,
+        ) {
+            Err(DiscoveryError::Metadata(MetadataError::IssuerMismatch { expected, found })) => {
+                assert_eq!(expected, "https://server.example.com/");
+                assert_eq!(found, "https://server.example.com");
+            }
+            res => panic!("unexpected result: {res:?}"),
+        }
+    }
+
+    #[test]
+    fn test_discover_not_found() {
+        let client = MockHttpClient::new();
+        client.push_response(HttpResponse::new(404, b"not found".to_vec()));
+        assert!(matches!(
+            ServerMetadata::discover(
+                &client,
+                "https://server.example.com",
+                WellKnown::OpenIdConfiguration,
+            ),
+            Err(DiscoveryError::Request(RequestError::UnexpectedStatus(
+                404,
+                _
+            )))
+        ));
+    }
+
+    #[test]
+    fn test_discover_error_status_is_not_oauth_error() {
+        let client = MockHttpClient::new();
+        client.push_response(HttpResponse::new(
+            400,
+            br#"{"error": "invalid_request"}"#.to_vec(),
+        ));
+        assert!(matches!(
+            ServerMetadata::discover(
+                &client,
+                "https://server.example.com",
+                WellKnown::OpenIdConfiguration,
+            ),
+            Err(DiscoveryError::Request(RequestError::UnexpectedStatus(
+                400,
+                _
+            )))
+        ));
+    }
+
+    #[test]
+    fn test_metadata_requires_response_types() {
+        assert!(ServerMetadata::from_json(br#"{"issuer": "https://server.example.com"}"#).is_err());
+    }
+}
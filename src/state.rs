@@ -0,0 +1,141 @@
+use crate::random::Random;
+use crate::utils;
+use rand::{CryptoRng, RngCore};
+use std::error::Error;
+use std::fmt::Display;
+
+// RFC 6749 section 10.10 requires the probability of guessing a generated
+// value to be at most 2^-128, so anything below 16 octets is refused.
+pub const MIN_ENTROPY_BYTES: usize = 16;
+pub const DEFAULT_ENTROPY_BYTES: usize = 32;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum RandomValueError {
+    InsufficientEntropy(usize),
+}
+
+impl Display for RandomValueError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RandomValueError::InsufficientEntropy(n) => {
+                write!(f, "RandomValueError::InsufficientEntropy({n})")
+            }
+        }
+    }
+}
+
+impl Error for RandomValueError {}
+
+fn generate<R>(rng: &mut R, entropy_bytes: usize) -> Result<String, RandomValueError>
+where
+    R: RngCore + CryptoRng,
+{
+    if entropy_bytes < MIN_ENTROPY_BYTES {
+        return Err(RandomValueError::InsufficientEntropy(entropy_bytes));
+    }
+    let octet_seq = Random::new(rng).get_octet_sequence(entropy_bytes);
+    Ok(data_encoding::BASE64URL_NOPAD.encode(&octet_seq))
+}
+
+// State and Nonce only differ in what they are used for, so both are
+// generated from this one definition.
+macro_rules! random_value {
+    ($name:ident) => {
+        #[derive(Debug, Clone, Eq)]
+        pub struct $name(String);
+
+        impl $name {
+            pub fn new() -> Self {
+                Self::with_entropy(DEFAULT_ENTROPY_BYTES).expect("default entropy is sufficient")
+            }
+
+            pub fn with_entropy(entropy_bytes: usize) -> Result<Self, RandomValueError> {
+                Self::with_rng(&mut rand::thread_rng(), entropy_bytes)
+            }
+
+            pub fn with_rng<R>(rng: &mut R, entropy_bytes: usize) -> Result<Self, RandomValueError>
+            where
+                R: RngCore + CryptoRng,
+            {
+                generate(rng, entropy_bytes).map(Self)
+            }
+
+            pub fn from_string(value: String) -> Self {
+                Self(value)
+            }
+
+            pub fn secret(&self) -> String {
+                self.0.clone()
+            }
+
+            pub fn matches(&self, other: &str) -> bool {
+                utils::constant_time_eq(self.0.as_bytes(), other.as_bytes())
+            }
+        }
+
+        impl Default for $name {
+            fn default() -> Self {
+                Self::new()
+            }
+        }
+
+        impl PartialEq for $name {
+            fn eq(&self, other: &Self) -> bool {
+                self.matches(&other.0)
+            }
+        }
+    };
+}
+
+// Opaque value for the `state` parameter, used to bind the authorization
+// response to the user agent which started the request.
+random_value!(State);
+
+// Value for the OpenID Connect `nonce` parameter, which ties the ID token to
+// the client session and mitigates replay attacks.
+random_value!(Nonce);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_state_new() {
+        let state = State::new();
+        // 32 octets encode to 43 unpadded base64url characters.
+        assert_eq!(state.secret().len(), 43);
+        assert_ne!(state, State::new());
+    }
+
+    #[test]
+    fn test_with_entropy() {
+        assert_eq!(State::with_entropy(16).unwrap().secret().len(), 22);
+        assert_eq!(Nonce::with_entropy(64).unwrap().secret().len(), 86);
+        assert_eq!(
+            State::with_entropy(15),
+            Err(RandomValueError::InsufficientEntropy(15))
+        );
+        assert_eq!(
+            Nonce::with_entropy(0),
+            Err(RandomValueError::InsufficientEntropy(0))
+        );
+    }
+
+    #[test]
+    fn test_with_rng_is_deterministic() {
+        let first = Nonce::with_rng(&mut StdRng::seed_from_u64(1), 32).unwrap();
+        let second = Nonce::with_rng(&mut StdRng::seed_from_u64(1), 32).unwrap();
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn test_matches() {
+        let state = State::from_string("abc".into());
+        assert!(state.matches("abc"));
+        assert!(!state.matches("abd"));
+        assert!(!state.matches("abcd"));
+        assert!(!state.matches(""));
+    }
+}
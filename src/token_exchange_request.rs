@@ -0,0 +1,307 @@
+use crate::http::{AsyncHttpClient, HttpClient, RequestError};
+use crate::utils;
+use crate::{ClientAuth, OAuthParams, Token};
+use serde::de::DeserializeOwned;
+use std::fmt::Display;
+
+pub const TOKEN_EXCHANGE_GRANT_TYPE: &str = "urn:ietf:params:oauth:grant-type:token-exchange";
+
+// Token type identifiers (RFC 8693 section 3).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TokenType {
+    AccessToken,
+    RefreshToken,
+    IdToken,
+    Saml1,
+    Saml2,
+    Jwt,
+    Other(String),
+}
+
+impl TokenType {
+    pub fn as_str(&self) -> &str {
+        match self {
+            TokenType::AccessToken => "urn:ietf:params:oauth:token-type:access_token",
+            TokenType::RefreshToken => "urn:ietf:params:oauth:token-type:refresh_token",
+            TokenType::IdToken => "urn:ietf:params:oauth:token-type:id_token",
+            TokenType::Saml1 => "urn:ietf:params:oauth:token-type:saml1",
+            TokenType::Saml2 => "urn:ietf:params:oauth:token-type:saml2",
+            TokenType::Jwt => "urn:ietf:params:oauth:token-type:jwt",
+            TokenType::Other(s) => s,
+        }
+    }
+}
+
+impl From<&str> for TokenType {
+    fn from(s: &str) -> Self {
+        match s {
+            "urn:ietf:params:oauth:token-type:access_token" => TokenType::AccessToken,
+            "urn:ietf:params:oauth:token-type:refresh_token" => TokenType::RefreshToken,
+            "urn:ietf:params:oauth:token-type:id_token" => TokenType::IdToken,
+            "urn:ietf:params:oauth:token-type:saml1" => TokenType::Saml1,
+            "urn:ietf:params:oauth:token-type:saml2" => TokenType::Saml2,
+            "urn:ietf:params:oauth:token-type:jwt" => TokenType::Jwt,
+            other => TokenType::Other(other.to_owned()),
+        }
+    }
+}
+
+impl Display for TokenType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+// Token Exchange Request (RFC 8693 section 2.1).
+#[derive(Debug)]
+pub struct TokenExchangeRequest {
+    subject_token: String,
+    subject_token_type: TokenType,
+    actor_token: Option<(String, TokenType)>,
+    requested_token_type: Option<TokenType>,
+    resources: Option<Vec<String>>,
+    audiences: Option<Vec<String>>,
+    scope: Option<Vec<String>>,
+    extras: Option<Vec<(String, String)>>,
+    client_auth: Option<ClientAuth>,
+}
+
+impl OAuthParams for TokenExchangeRequest {
+    fn get_grant_type(&self) -> Option<String> {
+        TOKEN_EXCHANGE_GRANT_TYPE.to_owned().into()
+    }
+
+    fn get_scopes_mut(&mut self) -> Option<&mut Vec<String>> {
+        if self.scope.is_none() {
+            self.scope = Some(vec![])
+        }
+        self.scope.as_mut()
+    }
+
+    fn get_scopes_ref(&self) -> Option<&Vec<String>> {
+        self.scope.as_ref()
+    }
+
+    fn get_extra_params_mut(&mut self) -> Option<&mut Vec<(String, String)>> {
+        if self.extras.is_none() {
+            self.extras = Some(vec![])
+        }
+        self.extras.as_mut()
+    }
+
+    fn get_extra_params_ref(&self) -> Option<&Vec<(String, String)>> {
+        self.extras.as_ref()
+    }
+
+    fn get_client_auth(&self) -> Option<&ClientAuth> {
+        self.client_auth.as_ref()
+    }
+
+    fn get_subject_token(&self) -> Option<String> {
+        Some(self.subject_token.clone())
+    }
+
+    fn get_subject_token_type(&self) -> Option<String> {
+        Some(self.subject_token_type.as_str().to_owned())
+    }
+
+    fn get_actor_token(&self) -> Option<String> {
+        self.actor_token.as_ref().map(|(token, _)| token.clone())
+    }
+
+    fn get_actor_token_type(&self) -> Option<String> {
+        self.actor_token
+            .as_ref()
+            .map(|(_, token_type)| token_type.as_str().to_owned())
+    }
+
+    fn get_requested_token_type(&self) -> Option<String> {
+        self.requested_token_type
+            .as_ref()
+            .map(|t| t.as_str().to_owned())
+    }
+
+    fn get_resources_ref(&self) -> Option<&Vec<String>> {
+        self.resources.as_ref()
+    }
+
+    fn get_audiences_ref(&self) -> Option<&Vec<String>> {
+        self.audiences.as_ref()
+    }
+}
+
+impl TokenExchangeRequest {
+    pub fn new(subject_token: String, subject_token_type: TokenType) -> Self {
+        Self {
+            subject_token,
+            subject_token_type,
+            actor_token: None,
+            requested_token_type: None,
+            resources: None,
+            audiences: None,
+            scope: None,
+            extras: None,
+            client_auth: None,
+        }
+    }
+
+    pub fn set_actor_token(&mut self, actor_token: String, actor_token_type: TokenType) {
+        self.actor_token = Some((actor_token, actor_token_type));
+    }
+
+    pub fn set_requested_token_type(&mut self, requested_token_type: TokenType) {
+        self.requested_token_type = Some(requested_token_type);
+    }
+
+    pub fn add_resource(&mut self, resource: String) {
+        self.resources.get_or_insert_with(Vec::new).push(resource);
+    }
+
+    pub fn add_audience(&mut self, audience: String) {
+        self.audiences.get_or_insert_with(Vec::new).push(audience);
+    }
+
+    pub fn set_client_auth(&mut self, client_auth: ClientAuth) {
+        self.client_auth = Some(client_auth);
+    }
+
+    pub fn add_scope(&mut self, scope: String) {
+        utils::add_scope(self, scope);
+    }
+
+    pub fn add_scopes<I>(&mut self, scopes: I)
+    where
+        I: IntoIterator<Item = String>,
+    {
+        utils::add_scopes(self, scopes);
+    }
+
+    pub fn add_extra_param(&mut self, key: String, value: String) {
+        utils::add_extra_param(self, key, value);
+    }
+
+    pub fn get_request_params_as_vec(&self) -> Vec<(String, String)> {
+        utils::get_request_params_as_vec(self)
+    }
+
+    pub fn get_headers(&self) -> Vec<(String, String)> {
+        utils::get_headers(self)
+    }
+
+    pub fn get_request_body(&self) -> Vec<u8> {
+        utils::get_request_body(self)
+    }
+
+    pub fn execute<C, T>(&self, client: &C, token_url: &str) -> Result<T, RequestError<C::Error>>
+    where
+        C: HttpClient,
+        T: Token + DeserializeOwned,
+    {
+        utils::execute_token_request(self, client, token_url)
+    }
+
+    pub async fn execute_async<C, T>(
+        &self,
+        client: &C,
+        token_url: &str,
+    ) -> Result<T, RequestError<C::Error>>
+    where
+        C: AsyncHttpClient,
+        T: Token + DeserializeOwned,
+    {
+        utils::execute_token_request_async(self, client, token_url).await
+    }
+}
+
+#[cfg(test)]
+mod token_exchange_request_tests {
+    use super::*;
+    use crate::{HttpResponse, MockHttpClient, TokenResponse};
+
+    #[test]
+    fn test_request_params() {
+        let mut request = TokenExchangeRequest::new(
+            "accVkjcJyb4BWCxGsndESCJQbdFMogUC5PbRDqceLTC".into(),
+            TokenType::AccessToken,
+        );
+        request.add_resource("https://backend.example.com/api".into());
+        request.add_audience("urn:example:cooperation-context".into());
+        request.add_audience("urn:example:other".into());
+        request.set_requested_token_type(TokenType::Jwt);
+        request.set_actor_token("actor".into(), TokenType::IdToken);
+        request.add_scope("read".into());
+
+        assert_eq!(
+            request.get_request_params_as_vec(),
+            vec![
+                (
+                    "grant_type".to_owned(),
+                    TOKEN_EXCHANGE_GRANT_TYPE.to_owned()
+                ),
+                ("scope".to_owned(), "read".to_owned()),
+                (
+                    "subject_token".to_owned(),
+                    "accVkjcJyb4BWCxGsndESCJQbdFMogUC5PbRDqceLTC".to_owned()
+                ),
+                (
+                    "subject_token_type".to_owned(),
+                    "urn:ietf:params:oauth:token-type:access_token".to_owned()
+                ),
+                ("actor_token".to_owned(), "actor".to_owned()),
+                (
+                    "actor_token_type".to_owned(),
+                    "urn:ietf:params:oauth:token-type:id_token".to_owned()
+                ),
+                (
+                    "requested_token_type".to_owned(),
+                    "urn:ietf:params:oauth:token-type:jwt".to_owned()
+                ),
+                (
+                    "resource".to_owned(),
+                    "https://backend.example.com/api".to_owned()
+                ),
+                (
+                    "audience".to_owned(),
+                    "urn:example:cooperation-context".to_owned()
+                ),
+                ("audience".to_owned(), "urn:example:other".to_owned()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_token_type_round_trip() {
+        for t in [
+            TokenType::AccessToken,
+            TokenType::RefreshToken,
+            TokenType::IdToken,
+            TokenType::Saml1,
+            TokenType::Saml2,
+            TokenType::Jwt,
+            TokenType::Other("urn:example:custom".into()),
+        ] {
+            assert_eq!(TokenType::from(t.as_str()), t);
+        }
+    }
+
+    #[test]
+    fn test_execute_issued_token_type() {
+        let client = MockHttpClient::new();
+        client.push_response(HttpResponse::new(
+            200,
+            r#"{
+                "access_token": "eyJhbGciOiJFUzI1NiIsImtpZCI6IjllciJ9.e30.c2ln",
+                "issued_token_type": "urn:ietf:params:oauth:token-type:access_token",
+                "token_type": "Bearer",
+                "expires_in": 60
+            }"#
+            .into(),
+        ));
+        let request = TokenExchangeRequest::new("subject".into(), TokenType::AccessToken);
+
+        let token: TokenResponse = request
+            .execute(&client, "https://as.example.com/token")
+            .unwrap();
+        assert_eq!(token.issued_token_type(), Some(TokenType::AccessToken));
+    }
+}
@@ -0,0 +1,171 @@
+use crate::scope::Scope;
+use crate::utils;
+use crate::{Token, TokenType};
+use serde::{self, Deserialize};
+use serde_json::Value;
+use std::cmp::PartialEq;
+use std::collections::HashMap;
+use std::time::SystemTime;
+
+// Successful access token response (RFC 6749 section 5.1) which is the same
+// for every grant type. Any member not defined by section 5.1 is kept in
+// `extras` so that extensions like `id_token` are not lost.
+#[derive(Debug, Deserialize)]
+pub struct TokenResponse {
+    access_token: String,
+    token_type: String,
+    refresh_token: Option<String>,
+    expires_in: Option<u32>,
+    scope: Option<Scope>,
+    #[serde(flatten)]
+    extras: HashMap<String, Value>,
+    #[serde(skip, default = "SystemTime::now")]
+    generated_time: SystemTime,
+}
+
+impl PartialEq for TokenResponse {
+    fn eq(&self, other: &Self) -> bool {
+        self.access_token == other.access_token
+            && self.token_type == other.token_type
+            && self.refresh_token == other.refresh_token
+            && self.expires_in == other.expires_in
+            && self.scope == other.scope
+            && self.extras == other.extras
+    }
+}
+
+impl Eq for TokenResponse {}
+
+impl Token for TokenResponse {
+    fn set_refresh_token(&mut self, refresh_token: String) {
+        self.refresh_token = Some(refresh_token);
+    }
+
+    fn set_exprires_in(&mut self, expires_in: u32) {
+        self.expires_in = Some(expires_in);
+    }
+
+    fn set_scope(&mut self, scopes: Vec<String>) {
+        self.scope = Some(Scope::Str(scopes.join(" ")));
+    }
+
+    fn access_token(&self) -> String {
+        self.access_token.clone()
+    }
+
+    fn refresh_token(&self) -> Option<String> {
+        self.refresh_token.clone()
+    }
+
+    fn token_type(&self) -> String {
+        self.token_type.clone()
+    }
+
+    fn scopes(&self) -> Option<String> {
+        self.scope.as_ref().map(Scope::to_space_separated)
+    }
+
+    fn is_valid(&self) -> bool {
+        utils::is_token_valid(self.generated_time, self.expires_in)
+    }
+}
+
+impl TokenResponse {
+    pub fn new(access_token: String, token_type: String) -> Self {
+        TokenResponse {
+            access_token,
+            token_type,
+            refresh_token: None,
+            expires_in: None,
+            scope: None,
+            extras: HashMap::new(),
+            generated_time: SystemTime::now(),
+        }
+    }
+
+    pub fn expires_in(&self) -> Option<u32> {
+        self.expires_in
+    }
+
+    // RFC 8693 section 2.2.1, only present in token exchange responses.
+    pub fn issued_token_type(&self) -> Option<TokenType> {
+        self.extras
+            .get("issued_token_type")
+            .and_then(Value::as_str)
+            .map(TokenType::from)
+    }
+
+    // OpenID Connect Core 1.0 section 3.1.3.3, only present when the
+    // `openid` scope was requested.
+    pub fn id_token(&self) -> Option<String> {
+        self.extras
+            .get("id_token")
+            .and_then(Value::as_str)
+            .map(str::to_owned)
+    }
+
+    pub fn get_extra_field(&self, name: &str) -> Option<Value> {
+        self.extras.get(name).cloned()
+    }
+
+    pub fn get_extra_fields(&self) -> HashMap<String, Value> {
+        self.extras.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_token_response_deserialization() {
+        let json_data = r#"{
+            "access_token": "test_token",
+            "token_type": "Bearer",
+            "expires_in": 3600,
+            "scope": ["test_scope", "test_another_scope"]
+        }"#;
+        let token: TokenResponse = serde_json::from_str(json_data).unwrap();
+        assert_eq!(token.access_token(), "test_token");
+        assert_eq!(token.token_type(), "Bearer");
+        assert_eq!(token.refresh_token(), None);
+        assert_eq!(token.expires_in(), Some(3600));
+        assert_eq!(
+            token.scopes(),
+            Some(String::from("test_scope test_another_scope"))
+        );
+        assert!(token.get_extra_fields().is_empty());
+        assert!(token.is_valid());
+    }
+
+    #[test]
+    fn test_token_response_extra_fields() {
+        let json_data = r#"{
+            "access_token": "test_token",
+            "token_type": "N_A",
+            "issued_token_type": "urn:ietf:params:oauth:token-type:access_token",
+            "id_token": "eyJhbGciOiJSUzI1NiJ9.e30.c2ln",
+            "x_vendor": {"region": "eu", "tier": 2}
+        }"#;
+        let token: TokenResponse = serde_json::from_str(json_data).unwrap();
+        assert_eq!(token.get_extra_fields().len(), 3);
+        assert_eq!(
+            token.get_extra_field("issued_token_type"),
+            Some(json!("urn:ietf:params:oauth:token-type:access_token"))
+        );
+        assert_eq!(
+            token.get_extra_field("id_token"),
+            Some(json!("eyJhbGciOiJSUzI1NiJ9.e30.c2ln"))
+        );
+        assert_eq!(
+            token.get_extra_field("x_vendor"),
+            Some(json!({"region": "eu", "tier": 2}))
+        );
+        assert_eq!(token.get_extra_field("scope"), None);
+        assert_eq!(
+            token.id_token(),
+            Some("eyJhbGciOiJSUzI1NiJ9.e30.c2ln".to_owned())
+        );
+    }
+}
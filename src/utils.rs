@@ -1,4 +1,10 @@
-use crate::OAuthParams;
+use crate::http::{self, AsyncHttpClient, HttpClient, RequestError};
+use crate::{ClientAssertionError, ClientAuth, OAuthParams, Token};
+use serde::de::DeserializeOwned;
+use std::error::Error;
+use std::fmt::Display;
+use std::time::{SystemTime, UNIX_EPOCH};
+use subtle::ConstantTimeEq;
 
 pub fn append_to_vec<T, I>(v: &mut Vec<T>, items: I)
 where
@@ -13,9 +19,8 @@ pub(crate) fn add_scope<R>(oauth_req: &mut R, scope: String)
 where
     R: OAuthParams,
 {
-    match oauth_req.get_scopes_mut() {
-        Some(v) => v.push(scope),
-        None => {}
+    if let Some(v) = oauth_req.get_scopes_mut() {
+        v.push(scope);
     }
 }
 
@@ -24,9 +29,8 @@ where
     R: OAuthParams,
     I: IntoIterator<Item = String>,
 {
-    match oauth_req.get_scopes_mut() {
-        Some(v) => append_to_vec(v, scopes.into_iter()),
-        None => {}
+    if let Some(v) = oauth_req.get_scopes_mut() {
+        append_to_vec(v, scopes);
     }
 }
 
@@ -35,74 +39,521 @@ where
     R: OAuthParams,
 {
     let mut params = vec![];
-    match oauth_req.get_grant_type() {
-        Some(s) => params.push((String::from("grant_type"), s)),
-        None => {}
+    if let Some(s) = oauth_req.get_grant_type() {
+        params.push((String::from("grant_type"), s));
     }
-    match oauth_req.get_response_type() {
-        Some(s) => params.push((String::from("response_type"), s)),
-        None => {}
+    if let Some(s) = oauth_req.get_response_type() {
+        params.push((String::from("response_type"), s));
     }
-    match oauth_req.get_redirect_url() {
-        Some(s) => params.push((String::from("redirect_uri"), s)),
-        None => {}
+    if let Some(s) = oauth_req.get_redirect_url() {
+        params.push((String::from("redirect_uri"), s));
     }
-    match oauth_req.get_client_id() {
-        Some(s) => params.push((String::from("client_id"), s)),
-        None => {}
-    }
-    match oauth_req.get_client_secret() {
-        Some(s) => params.push((String::from("client_secret"), s)),
-        None => {}
+    match oauth_req.get_client_auth() {
+        Some(auth) => append_to_vec(&mut params, auth.get_request_params_as_vec()),
+        None => {
+            if let Some(s) = oauth_req.get_client_id() {
+                params.push((String::from("client_id"), s));
+            }
+            if let Some(s) = oauth_req.get_client_secret() {
+                params.push((String::from("client_secret"), s));
+            }
+        }
     }
     match oauth_req.get_scopes_ref() {
-        Some(v) if v.len() > 0 => {
+        Some(v) if !v.is_empty() => {
             params.push((String::from("scope"), v.join(" ")));
         }
         _ => {}
     }
-    match oauth_req.get_state() {
-        Some(s) => params.push((String::from("state"), s)),
-        None => {}
+    if let Some(s) = oauth_req.get_state() {
+        params.push((String::from("state"), s));
+    }
+    if let Some(s) = oauth_req.get_nonce() {
+        params.push((String::from("nonce"), s));
+    }
+    if let Some(s) = oauth_req.get_prompt() {
+        params.push((String::from("prompt"), s));
+    }
+    if let Some(s) = oauth_req.get_max_age() {
+        params.push((String::from("max_age"), s));
+    }
+    if let Some(s) = oauth_req.get_ui_locales() {
+        params.push((String::from("ui_locales"), s));
+    }
+    if let Some(s) = oauth_req.get_login_hint() {
+        params.push((String::from("login_hint"), s));
+    }
+    if let Some(s) = oauth_req.get_id_token_hint() {
+        params.push((String::from("id_token_hint"), s));
+    }
+    if let Some(s) = oauth_req.get_acr_values() {
+        params.push((String::from("acr_values"), s));
+    }
+    if let Some(s) = oauth_req.get_display() {
+        params.push((String::from("display"), s));
+    }
+    if let Some(s) = oauth_req.get_claims() {
+        params.push((String::from("claims"), s));
     }
     match oauth_req.get_extra_params_ref() {
-        Some(v) if v.len() > 0 => {
-            append_to_vec(&mut params, v.iter().map(|a| a.clone()));
+        Some(v) if !v.is_empty() => {
+            append_to_vec(&mut params, v.iter().cloned());
         }
         _ => {}
     }
-    match oauth_req.get_username() {
-        Some(s) => params.push((String::from("username"), s)),
-        None => {}
+    if let Some(s) = oauth_req.get_username() {
+        params.push((String::from("username"), s));
+    }
+    if let Some(s) = oauth_req.get_password() {
+        params.push((String::from("password"), s));
+    }
+    if let Some(s) = oauth_req.get_code() {
+        params.push((String::from("code"), s));
+    }
+    if let Some(s) = oauth_req.get_refresh_token() {
+        params.push((String::from("refresh_token"), s));
+    }
+    if let Some(s) = oauth_req.get_code_challenge() {
+        params.push((String::from("code_challenge"), s));
+    }
+    if let Some(s) = oauth_req.get_code_challenge_method() {
+        params.push((String::from("code_challenge_method"), s));
     }
-    match oauth_req.get_password() {
-        Some(s) => params.push((String::from("password"), s)),
-        None => {}
+    if let Some(s) = oauth_req.get_code_verifier() {
+        params.push((String::from("code_verifier"), s));
     }
-    match oauth_req.get_code() {
-        Some(s) => params.push((String::from("code"), s)),
-        None => {}
+    if let Some(s) = oauth_req.get_device_code() {
+        params.push((String::from("device_code"), s));
     }
-    match oauth_req.get_refresh_token() {
-        Some(s) => params.push((String::from("refresh_token"), s)),
-        None => {}
+    if let Some(s) = oauth_req.get_token() {
+        params.push((String::from("token"), s));
     }
-    return params;
+    if let Some(s) = oauth_req.get_token_type_hint() {
+        params.push((String::from("token_type_hint"), s));
+    }
+    if let Some(s) = oauth_req.get_assertion() {
+        params.push((String::from("assertion"), s));
+    }
+    if let Some(s) = oauth_req.get_subject_token() {
+        params.push((String::from("subject_token"), s));
+    }
+    if let Some(s) = oauth_req.get_subject_token_type() {
+        params.push((String::from("subject_token_type"), s));
+    }
+    if let Some(s) = oauth_req.get_actor_token() {
+        params.push((String::from("actor_token"), s));
+    }
+    if let Some(s) = oauth_req.get_actor_token_type() {
+        params.push((String::from("actor_token_type"), s));
+    }
+    if let Some(s) = oauth_req.get_requested_token_type() {
+        params.push((String::from("requested_token_type"), s));
+    }
+    if let Some(v) = oauth_req.get_resources_ref() {
+        append_to_vec(
+            &mut params,
+            v.iter().map(|s| (String::from("resource"), s.clone())),
+        );
+    }
+    if let Some(v) = oauth_req.get_audiences_ref() {
+        append_to_vec(
+            &mut params,
+            v.iter().map(|s| (String::from("audience"), s.clone())),
+        );
+    }
+    params
 }
 
 pub(crate) fn add_extra_param<R>(oauth_req: &mut R, key: String, val: String)
 where
     R: OAuthParams,
 {
-    match oauth_req.get_extra_params_mut() {
-        Some(v) => v.push((key, val)),
-        None => {}
+    if let Some(v) = oauth_req.get_extra_params_mut() {
+        v.push((key, val));
     }
 }
 
-pub(crate) fn get_headers() -> Vec<(String, String)> {
-    return vec![(
+pub(crate) fn get_headers<R>(oauth_req: &R) -> Vec<(String, String)>
+where
+    R: OAuthParams,
+{
+    let mut headers = vec![(
         "Content-Type".into(),
         "application/x-www-form-urlencoded".into(),
     )];
+    if let Some(auth) = oauth_req.get_client_auth() {
+        append_to_vec(&mut headers, auth.get_headers());
+    }
+    headers
+}
+
+// Encodes a single name or value using the application/x-www-form-urlencoded
+// rules of RFC 6749 Appendix B: the RFC 3986 unreserved characters are kept
+// as is, space becomes '+' and every other UTF-8 octet is percent-encoded.
+pub(crate) fn form_urlencode(s: &str) -> String {
+    let mut encoded = String::with_capacity(s.len());
+    for b in s.bytes() {
+        match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                encoded.push(b as char)
+            }
+            b' ' => encoded.push('+'),
+            _ => encoded.push_str(&format!("%{b:02X}")),
+        }
+    }
+    encoded
+}
+
+pub(crate) fn form_urlencode_params(params: &[(String, String)]) -> String {
+    params
+        .iter()
+        .map(|(k, v)| format!("{}={}", form_urlencode(k), form_urlencode(v)))
+        .collect::<Vec<_>>()
+        .join("&")
+}
+
+pub(crate) fn get_request_body<R>(oauth_req: &R) -> Vec<u8>
+where
+    R: OAuthParams,
+{
+    form_urlencode_params(&get_request_params_as_vec(oauth_req)).into_bytes()
+}
+
+// Body sent by execute, which also carries the client assertion. It is
+// produced for every request, and producing it can fail.
+pub(crate) fn get_request_body_with_assertion<R>(
+    oauth_req: &R,
+) -> Result<Vec<u8>, ClientAssertionError>
+where
+    R: OAuthParams,
+{
+    let mut params = get_request_params_as_vec(oauth_req);
+    let assertion = oauth_req
+        .get_client_auth()
+        .map(ClientAuth::get_client_assertion)
+        .transpose()?
+        .flatten();
+    if let Some(assertion) = assertion {
+        params.push((String::from("client_assertion"), assertion));
+    }
+    Ok(form_urlencode_params(&params).into_bytes())
+}
+
+pub(crate) fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.ct_eq(b).into()
+}
+
+pub(crate) fn get_unix_time() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+pub(crate) fn is_token_valid(generated_time: SystemTime, expires_in: Option<u32>) -> bool {
+    match expires_in {
+        Some(expires_in) => {
+            match generated_time.elapsed() {
+                Ok(elapsed) => (elapsed.as_secs() as u32) < expires_in,
+                // if err, then assume that the token is valid so that the
+                // user can try accessing the protected resource using the
+                // current token.
+                Err(_) => true,
+            }
+        }
+        // if None, then the auth server did not provide the expiration
+        // info. So, there is no other choice but to use the access token
+        // and try to access the protected resource.
+        None => true,
+    }
+}
+
+pub(crate) fn execute_request<R, C, T>(
+    oauth_req: &R,
+    client: &C,
+    url: &str,
+) -> Result<T, RequestError<C::Error>>
+where
+    R: OAuthParams,
+    C: HttpClient,
+    T: DeserializeOwned,
+{
+    let body = get_request_body_with_assertion(oauth_req).map_err(RequestError::ClientAssertion)?;
+    let request = http::post_form(url, get_headers(oauth_req), body);
+    let response = client.execute(request).map_err(RequestError::Transport)?;
+    http::parse_token_response(response)
+}
+
+pub(crate) async fn execute_request_async<R, C, T>(
+    oauth_req: &R,
+    client: &C,
+    url: &str,
+) -> Result<T, RequestError<C::Error>>
+where
+    R: OAuthParams,
+    C: AsyncHttpClient,
+    T: DeserializeOwned,
+{
+    let body = get_request_body_with_assertion(oauth_req).map_err(RequestError::ClientAssertion)?;
+    let request = http::post_form(url, get_headers(oauth_req), body);
+    let response = client
+        .execute(request)
+        .await
+        .map_err(RequestError::Transport)?;
+    http::parse_token_response(response)
+}
+
+pub(crate) fn execute_request_without_content<R, C>(
+    oauth_req: &R,
+    client: &C,
+    url: &str,
+) -> Result<(), RequestError<C::Error>>
+where
+    R: OAuthParams,
+    C: HttpClient,
+{
+    let body = get_request_body_with_assertion(oauth_req).map_err(RequestError::ClientAssertion)?;
+    let request = http::post_form(url, get_headers(oauth_req), body);
+    let response = client.execute(request).map_err(RequestError::Transport)?;
+    http::parse_empty_response(response)
+}
+
+pub(crate) async fn execute_request_without_content_async<R, C>(
+    oauth_req: &R,
+    client: &C,
+    url: &str,
+) -> Result<(), RequestError<C::Error>>
+where
+    R: OAuthParams,
+    C: AsyncHttpClient,
+{
+    let body = get_request_body_with_assertion(oauth_req).map_err(RequestError::ClientAssertion)?;
+    let request = http::post_form(url, get_headers(oauth_req), body);
+    let response = client
+        .execute(request)
+        .await
+        .map_err(RequestError::Transport)?;
+    http::parse_empty_response(response)
+}
+
+pub(crate) fn execute_token_request<R, C, T>(
+    oauth_req: &R,
+    client: &C,
+    token_url: &str,
+) -> Result<T, RequestError<C::Error>>
+where
+    R: OAuthParams,
+    C: HttpClient,
+    T: Token + DeserializeOwned,
+{
+    execute_request(oauth_req, client, token_url)
+}
+
+pub(crate) async fn execute_token_request_async<R, C, T>(
+    oauth_req: &R,
+    client: &C,
+    token_url: &str,
+) -> Result<T, RequestError<C::Error>>
+where
+    R: OAuthParams,
+    C: AsyncHttpClient,
+    T: Token + DeserializeOwned,
+{
+    execute_request_async(oauth_req, client, token_url).await
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum FormDecodeError {
+    InvalidPercentEncoding,
+    InvalidUtf8,
+}
+
+impl Display for FormDecodeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        use FormDecodeError::*;
+        write!(
+            f,
+            "FormDecodeError::{}",
+            match self {
+                InvalidPercentEncoding => "InvalidPercentEncoding",
+                InvalidUtf8 => "InvalidUtf8",
+            }
+        )
+    }
+}
+
+impl Error for FormDecodeError {}
+
+fn hex_value(b: u8) -> Option<u8> {
+    match b {
+        b'0'..=b'9' => Some(b - b'0'),
+        b'a'..=b'f' => Some(b - b'a' + 10),
+        b'A'..=b'F' => Some(b - b'A' + 10),
+        _ => None,
+    }
+}
+
+// Reverses form_urlencode: '+' becomes space, "%XX" becomes the octet XX and
+// the resulting octets must form valid UTF-8.
+pub(crate) fn form_urldecode(s: &[u8]) -> Result<String, FormDecodeError> {
+    let mut decoded = Vec::with_capacity(s.len());
+    let mut iter = s.iter();
+    while let Some(&b) = iter.next() {
+        match b {
+            b'+' => decoded.push(b' '),
+            b'%' => {
+                let hi = iter.next().copied().and_then(hex_value);
+                let lo = iter.next().copied().and_then(hex_value);
+                match (hi, lo) {
+                    (Some(hi), Some(lo)) => decoded.push(hi << 4 | lo),
+                    _ => return Err(FormDecodeError::InvalidPercentEncoding),
+                }
+            }
+            _ => decoded.push(b),
+        }
+    }
+    String::from_utf8(decoded).map_err(|_| FormDecodeError::InvalidUtf8)
+}
+
+pub fn decode_form_params(input: &[u8]) -> Result<Vec<(String, String)>, FormDecodeError> {
+    input
+        .split(|&b| b == b'&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (key, value) = match pair.iter().position(|&b| b == b'=') {
+                Some(i) => (&pair[..i], &pair[i + 1..]),
+                None => (pair, &pair[pair.len()..]),
+            };
+            Ok((form_urldecode(key)?, form_urldecode(value)?))
+        })
+        .collect()
+}
+
+pub(crate) fn get_url_with_params<R>(url: &str, oauth_req: &R) -> String
+where
+    R: OAuthParams,
+{
+    let params = get_request_params_as_vec(oauth_req);
+    // the fragment component is not allowed in an endpoint URI (RFC 6749
+    // section 3.1), so drop it if the caller passed one in.
+    let url = url.split('#').next().unwrap_or_default();
+    let (base, query) = match url.split_once('?') {
+        Some((base, query)) => (base, query),
+        None => (url, ""),
+    };
+    // the existing query component must be retained, but a parameter must
+    // not be sent more than once. So, the ones set on the request win.
+    let overridden = params
+        .iter()
+        .map(|(k, _)| form_urlencode(k))
+        .collect::<Vec<_>>();
+    let mut components = query
+        .split('&')
+        .filter(|c| !c.is_empty())
+        .filter(|c| {
+            let key = c.split('=').next().unwrap_or_default();
+            !overridden.iter().any(|k| k == key)
+        })
+        .map(String::from)
+        .collect::<Vec<_>>();
+    if !params.is_empty() {
+        components.push(form_urlencode_params(&params));
+    }
+    if components.is_empty() {
+        return base.to_owned();
+    }
+    format!("{}?{}", base, components.join("&"))
+}
+
+#[cfg(test)]
+mod utils_tests {
+    use super::*;
+
+    #[test]
+    fn test_form_urlencode_unreserved() {
+        assert_eq!(form_urlencode("azAZ09-._~"), "azAZ09-._~");
+    }
+
+    #[test]
+    fn test_form_urlencode_reserved() {
+        assert_eq!(
+            form_urlencode("a b+c&d=e/f?g#h%"),
+            "a+b%2Bc%26d%3De%2Ff%3Fg%23h%25"
+        );
+    }
+
+    #[test]
+    fn test_form_urlencode_rfc6749_appendix_b() {
+        assert_eq!(form_urlencode(" %&+£€"), "+%25%26%2B%C2%A3%E2%82%AC");
+    }
+
+    #[test]
+    fn test_form_urlencode_params() {
+        let params = vec![
+            ("scope".to_owned(), "openid email".to_owned()),
+            ("state".to_owned(), "a&b".to_owned()),
+        ];
+        assert_eq!(
+            form_urlencode_params(&params),
+            "scope=openid+email&state=a%26b"
+        );
+    }
+
+    #[test]
+    fn test_form_urldecode() {
+        assert_eq!(
+            form_urldecode(b"+%25%26%2B%C2%A3%E2%82%AC"),
+            Ok(String::from(" %&+£€"))
+        );
+        assert_eq!(form_urldecode(b"%2f%2F"), Ok(String::from("//")));
+    }
+
+    #[test]
+    fn test_form_urldecode_invalid_percent_encoding() {
+        assert_eq!(
+            form_urldecode(b"abc%2"),
+            Err(FormDecodeError::InvalidPercentEncoding)
+        );
+        assert_eq!(
+            form_urldecode(b"abc%zz"),
+            Err(FormDecodeError::InvalidPercentEncoding)
+        );
+    }
+
+    #[test]
+    fn test_form_urldecode_invalid_utf8() {
+        assert_eq!(form_urldecode(b"%C3%28"), Err(FormDecodeError::InvalidUtf8));
+    }
+
+    #[test]
+    fn test_decode_form_params() {
+        let params = decode_form_params(b"a=1&&b=&c&d=x%3Dy=z").unwrap();
+        assert_eq!(
+            params,
+            vec![
+                ("a".to_owned(), "1".to_owned()),
+                ("b".to_owned(), "".to_owned()),
+                ("c".to_owned(), "".to_owned()),
+                ("d".to_owned(), "x=y=z".to_owned()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_form_params_round_trip() {
+        let params = vec![
+            ("grant_type".to_owned(), "password".to_owned()),
+            ("username".to_owned(), "jöhn döe@example.com".to_owned()),
+            ("password".to_owned(), "p@ss w0rd+&=%#?/\\\"'".to_owned()),
+            ("scope".to_owned(), "read write".to_owned()),
+            ("emoji".to_owned(), "🔑".to_owned()),
+            ("empty".to_owned(), "".to_owned()),
+            ("ключ".to_owned(), "значение".to_owned()),
+        ];
+        let encoded = form_urlencode_params(&params);
+        assert!(encoded
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b"-._~+%&=".contains(&b)));
+        assert_eq!(decode_form_params(encoded.as_bytes()), Ok(params));
+    }
 }